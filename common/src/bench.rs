@@ -0,0 +1,124 @@
+//! Times the parts of a solution for the `--bench` flag.
+//!
+//! The parts are run over and over inside the one process, on input that has already been read, so the
+//! numbers leave out process start-up and file reading. They are printed as `Part 1 time: <timing>` lines
+//! which the report reads back in with `Timing::from_str`.
+
+use std::{
+    fmt::Display,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use thiserror::Error;
+
+/// The fastest, median and slowest of a number of timed runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timing {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+/// Runs `f` `runs` times, or once if `runs` is 0, and times each run.
+pub fn time<T>(runs: usize, mut f: impl FnMut() -> T) -> Timing {
+    let mut times = (0..runs.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            // Stops the compiler from skipping work whose result is never used.
+            std::hint::black_box(f());
+            start.elapsed()
+        })
+        .collect::<Vec<_>>();
+    times.sort();
+    Timing {
+        runs: times.len(),
+        min: times[0],
+        median: times[times.len() / 2],
+        max: times[times.len() - 1],
+    }
+}
+
+impl Display for Timing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "runs={} min={}ns median={}ns max={}ns",
+            self.runs,
+            self.min.as_nanos(),
+            self.median.as_nanos(),
+            self.max.as_nanos()
+        )
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TimingParseError {
+    #[error("Expected `{0}=` in the timing")]
+    MissingField(&'static str),
+    #[error("Invalid value for `{0}` in the timing: {1}")]
+    InvalidValue(&'static str, String),
+}
+
+impl FromStr for Timing {
+    type Err = TimingParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let field = |name: &'static str| {
+            let value = s
+                .split_whitespace()
+                .find_map(|part| part.strip_prefix(name)?.strip_prefix('='))
+                .ok_or(TimingParseError::MissingField(name))?;
+            value
+                .trim_end_matches("ns")
+                .parse::<u64>()
+                .map_err(|_| TimingParseError::InvalidValue(name, value.to_string()))
+        };
+        Ok(Timing {
+            runs: field("runs")? as usize,
+            min: Duration::from_nanos(field("min")?),
+            median: Duration::from_nanos(field("median")?),
+            max: Duration::from_nanos(field("max")?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_runs() {
+        let mut calls = 0;
+        let timing = time(5, || calls += 1);
+        assert_eq!((calls, timing.runs), (5, 5));
+        assert!(timing.min <= timing.median && timing.median <= timing.max);
+        // Always runs at least once.
+        assert_eq!(time(0, || ()).runs, 1);
+    }
+
+    #[test]
+    fn test_timing_round_trip() {
+        let timing = Timing {
+            runs: 10,
+            min: Duration::from_nanos(1200),
+            median: Duration::from_micros(3),
+            max: Duration::from_millis(1),
+        };
+        let printed = timing.to_string();
+        assert_eq!(printed, "runs=10 min=1200ns median=3000ns max=1000000ns");
+        assert_eq!(printed.parse(), Ok(timing));
+    }
+
+    #[test]
+    fn test_timing_parse_errors() {
+        assert_eq!(
+            "runs=3 min=1ns max=2ns".parse::<Timing>(),
+            Err(TimingParseError::MissingField("median"))
+        );
+        assert_eq!(
+            "runs=3 min=1ns median=fast max=2ns".parse::<Timing>(),
+            Err(TimingParseError::InvalidValue("median", "fast".to_string()))
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Records the steps a solution takes, for working out why an answer is wrong.
+//!
+//! Solutions call `record` at interesting points (every instruction, every choice made, etc).
+//! Recording is off unless the runner was started with `--explain`, in which case each event is written
+//! out as it happens. When it's off, `record` is a single relaxed atomic load and the event is never built.
+
+use std::{
+    fmt::Display,
+    io::Write,
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static SINK: Mutex<Option<Sink>> = Mutex::new(None);
+
+/// Where recorded events are written.
+pub enum Sink {
+    /// One event per line in a human readable format, e.g. to the terminal.
+    Pretty(Box<dyn Write + Send>),
+    /// One JSON object per line, e.g. to a file for digging through later.
+    JsonLines(Box<dyn Write + Send>),
+}
+impl Sink {
+    fn write(&mut self, event: &Event) -> std::io::Result<()> {
+        match self {
+            Sink::Pretty(w) => writeln!(w, "{event}"),
+            Sink::JsonLines(w) => writeln!(w, "{}", event.to_json()),
+        }
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Sink::Pretty(w) | Sink::JsonLines(w) => w.flush(),
+        }
+    }
+}
+
+/// A single step of a solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// What kind of step this is, e.g. `dial_instruction`.
+    pub kind: &'static str,
+    pub fields: Vec<(&'static str, Value)>,
+}
+impl Event {
+    pub fn new(kind: &'static str) -> Self {
+        Self {
+            kind,
+            fields: vec![],
+        }
+    }
+    /// Adds a named field to the event.
+    pub fn with(mut self, name: &'static str, value: impl Into<Value>) -> Self {
+        self.fields.push((name, value.into()));
+        self
+    }
+    fn to_json(&self) -> String {
+        let mut json = format!("{{\"kind\":{}", json_string(self.kind));
+        for (name, value) in &self.fields {
+            let value = match value {
+                Value::Int(n) => n.to_string(),
+                Value::Bool(b) => b.to_string(),
+                Value::Text(s) => json_string(s),
+            };
+            json.push_str(&format!(",{}:{}", json_string(name), value));
+        }
+        json.push('}');
+        json
+    }
+}
+impl Display for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<20}", self.kind)?;
+        for (name, value) in &self.fields {
+            write!(f, " {name}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The value of a single field of an event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Int(i128),
+    Bool(bool),
+    Text(String),
+}
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Int(n) => write!(f, "{n}"),
+            Value::Bool(b) => write!(f, "{b}"),
+            Value::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+macro_rules! impl_int_value {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Value {
+            fn from(n: $t) -> Self {
+                Value::Int(n as i128)
+            }
+        })*
+    };
+}
+impl_int_value!(u8, u32, u64, usize, i32, i64);
+impl From<u128> for Value {
+    fn from(n: u128) -> Self {
+        // Only the very largest counts don't fit, and those are still worth seeing exactly.
+        match i128::try_from(n) {
+            Ok(n) => Value::Int(n),
+            Err(_) => Value::Text(n.to_string()),
+        }
+    }
+}
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Text(s.to_string())
+    }
+}
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::Text(s)
+    }
+}
+
+/// Turns recording on, all events from now on are written to the sink.
+pub fn enable(sink: Sink) {
+    *SINK.lock().unwrap_or_else(|e| e.into_inner()) = Some(sink);
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Turns recording off and flushes anything that has been written so far.
+pub fn finish() -> std::io::Result<()> {
+    ENABLED.store(false, Ordering::Relaxed);
+    match SINK.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        Some(mut sink) => sink.flush(),
+        None => Ok(()),
+    }
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Marks the start of a part, so that the events of each part can be told apart when running both.
+pub fn start_part(part: u32) {
+    record(|| Event::new("part").with("part", part));
+}
+
+/// Records an event. The closure is only called if recording is on, so building the event costs nothing otherwise.
+#[inline]
+pub fn record(event: impl FnOnce() -> Event) {
+    if is_enabled() {
+        write_event(&event());
+    }
+}
+
+#[cold]
+fn write_event(event: &Event) {
+    if let Some(sink) = SINK.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        // Explaining is a debugging aid, so failing to write an event shouldn't stop the solution.
+        if let Err(e) = sink.write(event) {
+            log::warn!("Failed to write explain event: {e}");
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_event() -> Event {
+        Event::new("dial_instruction")
+            .with("index", 0usize)
+            .with("instruction", "L68")
+            .with("from", 50u32)
+            .with("to", 82u32)
+            .with("landed", false)
+    }
+
+    #[test]
+    fn test_pretty_event() {
+        let expected = "dial_instruction     index=0 instruction=L68 from=50 to=82 landed=false";
+        assert_eq!(example_event().to_string(), expected);
+    }
+
+    #[test]
+    fn test_json_event() {
+        let expected = r#"{"kind":"dial_instruction","index":0,"instruction":"L68","from":50,"to":82,"landed":false}"#;
+        assert_eq!(example_event().to_json(), expected);
+    }
+
+    #[test]
+    fn test_json_string_escapes() {
+        assert_eq!(json_string("a\"b\\c\n"), r#""a\"b\\c\n""#);
+    }
+
+    #[test]
+    fn test_record_is_lazy_when_disabled() {
+        // Recording is never enabled in the tests, so the event must not be built.
+        record(|| panic!("Event should not be built when recording is off"));
+    }
+}
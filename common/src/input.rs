@@ -0,0 +1,144 @@
+use std::{fs::File, path::Path};
+
+use memmap2::Mmap;
+
+/// The byte order mark that some Windows editors put at the start of a file.
+const BOM: char = '\u{feff}';
+
+/// How much an input should be cleaned up before it is handed to a day's parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Normalization {
+    /// Strip the BOM, convert all line endings to `\n` and trim trailing whitespace from every line.
+    #[default]
+    Full,
+    /// Strip the BOM and convert all line endings to `\n`, but leave every other character alone.
+    /// This is the opt-out for days where the exact column widths of the input matter.
+    LineEndingsOnly,
+}
+
+/// Cleans up an input so that it looks the same no matter which platform it was saved on.
+/// A trailing newline is kept if there was one.
+pub fn normalize(input: &str, normalization: Normalization) -> String {
+    let input = input.strip_prefix(BOM).unwrap_or(input);
+    // Windows line endings first, then any lone carriage returns that are left over.
+    let unified = input.replace("\r\n", "\n").replace('\r', "\n");
+    match normalization {
+        Normalization::LineEndingsOnly => unified,
+        Normalization::Full => unified
+            .split('\n')
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// Reads an input file into a string and normalizes it.
+pub fn read_normalized(path: &Path, normalization: Normalization) -> std::io::Result<String> {
+    let raw = std::fs::read_to_string(path)?;
+    Ok(normalize(&raw, normalization))
+}
+
+/// An input file that is memory mapped rather than read into a `String`.
+/// Meant for inputs that are too big to comfortably load, days that can do their work in a single pass
+/// consume it line by line through `lines`.
+#[derive(Debug)]
+pub struct MappedInput {
+    mmap: Option<Mmap>,
+}
+impl MappedInput {
+    /// Memory maps the file and checks that it is valid UTF-8.
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        let file = File::open(path)?;
+        // Mapping an empty file fails on some platforms, and there's nothing to map anyway.
+        if file.metadata()?.len() == 0 {
+            return Ok(Self { mmap: None });
+        }
+        // SAFETY: The map is read only. If another process truncates or rewrites the file while
+        // we're running the contents can change under us, which we accept for puzzle inputs.
+        let mmap = unsafe { Mmap::map(&file)? };
+        std::str::from_utf8(&mmap)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Ok(Self { mmap: Some(mmap) })
+    }
+    /// The whole file as a string, with the BOM stripped if there is one.
+    /// Line endings are left as they are in the file.
+    pub fn as_str(&self) -> &str {
+        let bytes = self.mmap.as_deref().unwrap_or_default();
+        // SAFETY: Checked to be valid UTF-8 when the file was opened.
+        let s = unsafe { std::str::from_utf8_unchecked(bytes) };
+        s.strip_prefix(BOM).unwrap_or(s)
+    }
+    /// Iterates over the lines of the file, normalized the same way as `Normalization::Full`.
+    /// Blank lines are kept, since some inputs use them as separators.
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.as_str().lines().map(str::trim_end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_crlf() {
+        let input = "3-5\r\n10-14\r\n\r\n1\r\n5\r\n";
+        assert_eq!(
+            normalize(input, Normalization::Full),
+            "3-5\n10-14\n\n1\n5\n"
+        );
+    }
+
+    #[test]
+    fn test_normalize_lone_carriage_return() {
+        assert_eq!(normalize("L68\rR48", Normalization::Full), "L68\nR48");
+    }
+
+    #[test]
+    fn test_normalize_strips_bom() {
+        let input = "\u{feff}11-22,95-115";
+        assert_eq!(normalize(input, Normalization::Full), "11-22,95-115");
+        assert_eq!(
+            normalize(input, Normalization::LineEndingsOnly),
+            "11-22,95-115"
+        );
+    }
+
+    #[test]
+    fn test_normalize_trailing_whitespace() {
+        let input = "123 \t\r\n456  \n";
+        assert_eq!(normalize(input, Normalization::Full), "123\n456\n");
+    }
+
+    #[test]
+    fn test_line_endings_only_keeps_columns() {
+        let input = "123 328 \r\n 45 64  \r\n*   +   \r\n";
+        let expected = "123 328 \n 45 64  \n*   +   \n";
+        assert_eq!(normalize(input, Normalization::LineEndingsOnly), expected);
+    }
+
+    #[test]
+    fn test_normalize_already_clean() {
+        let input = "987654321111111\n811111111111119";
+        assert_eq!(normalize(input, Normalization::Full), input);
+    }
+
+    #[test]
+    fn test_mapped_input_lines() {
+        let path = std::env::temp_dir().join(format!("mapped_input_{}.txt", std::process::id()));
+        std::fs::write(&path, "\u{feff}L68 \r\nL30\r\n\r\nR48\n").unwrap();
+        let input = MappedInput::open(&path).unwrap();
+        let lines = input.lines().collect::<Vec<_>>();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(lines, vec!["L68", "L30", "", "R48"]);
+    }
+
+    #[test]
+    fn test_mapped_input_empty_file() {
+        let path = std::env::temp_dir().join(format!("mapped_empty_{}.txt", std::process::id()));
+        std::fs::write(&path, "").unwrap();
+        let input = MappedInput::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(input.as_str(), "");
+        assert_eq!(input.lines().count(), 0);
+    }
+}
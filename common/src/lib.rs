@@ -0,0 +1,8 @@
+//! Shared helpers used by the runner of every day.
+//! Each day is still its own crate, this just stops the same plumbing being copied around.
+
+pub mod bench;
+pub mod explain;
+pub mod input;
+pub mod params;
+pub mod svg;
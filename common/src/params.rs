@@ -0,0 +1,216 @@
+use std::{collections::HashMap, fmt::Display, path::Path, str::FromStr};
+
+use thiserror::Error;
+
+/// A named tuning knob declared by a day, along with its default value.
+/// Days declare these as consts and list them in their `PARAMS` slice.
+#[derive(Debug, Clone, Copy)]
+pub struct Param<T> {
+    pub name: &'static str,
+    pub default: T,
+    pub description: &'static str,
+}
+impl<T> Param<T> {
+    pub const fn new(name: &'static str, default: T, description: &'static str) -> Self {
+        Self {
+            name,
+            default,
+            description,
+        }
+    }
+}
+
+/// Type erased view of a `Param` so that a day can list all of its parameters in one slice.
+pub trait ParamSpec: Sync {
+    fn name(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    fn default_string(&self) -> String;
+    /// Checks that the raw value can be parsed into the type of the parameter.
+    fn validate(&self, raw: &str) -> Result<(), ParamError>;
+}
+impl<T> ParamSpec for Param<T>
+where
+    T: FromStr + Display + Sync,
+    T::Err: Display,
+{
+    fn name(&self) -> &'static str {
+        self.name
+    }
+    fn description(&self) -> &'static str {
+        self.description
+    }
+    fn default_string(&self) -> String {
+        self.default.to_string()
+    }
+    fn validate(&self, raw: &str) -> Result<(), ParamError> {
+        raw.parse::<T>()
+            .map(|_| ())
+            .map_err(|e| ParamError::InvalidValue {
+                name: self.name.to_string(),
+                value: raw.to_string(),
+                reason: e.to_string(),
+            })
+    }
+}
+
+/// The parameter values for a single run.
+/// Any parameter that has not been overridden falls back to its default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params {
+    overrides: HashMap<String, String>,
+}
+impl Params {
+    /// Builds the parameters for a run from a list of `(key, value)` overrides.
+    /// Every key must be declared in `specs` and every value must parse into the declared type.
+    /// Later overrides of the same key win.
+    pub fn new<I>(specs: &[&dyn ParamSpec], overrides: I) -> Result<Self, ParamError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut map = HashMap::new();
+        for (key, value) in overrides {
+            let spec = specs
+                .iter()
+                .find(|spec| spec.name() == key)
+                .ok_or_else(|| ParamError::UnknownParam {
+                    name: key.clone(),
+                    known: known_names(specs),
+                })?;
+            spec.validate(&value)?;
+            map.insert(key, value);
+        }
+        Ok(Self { overrides: map })
+    }
+    /// Returns the value of the given parameter, or its default if it wasn't overridden.
+    pub fn get<T>(&self, param: &Param<T>) -> T
+    where
+        T: FromStr + Clone,
+    {
+        match self.overrides.get(param.name) {
+            // The value was checked against the spec when the `Params` were built,
+            // so this can only fail if two params share a name but not a type.
+            Some(raw) => raw
+                .parse()
+                .unwrap_or_else(|_| panic!("Parameter {} was not validated", param.name)),
+            None => param.default.clone(),
+        }
+    }
+}
+
+/// Parses a single `key=value` assignment, as passed to `--param` or written in a params file.
+pub fn parse_assignment(s: &str) -> Result<(String, String), ParamError> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| ParamError::MissingEquals(s.to_string()))?;
+    let (key, value) = (key.trim(), value.trim());
+    if key.is_empty() {
+        return Err(ParamError::MissingEquals(s.to_string()));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// Parses the contents of a params file.
+/// One `key=value` per line, blank lines and lines starting with `#` are ignored.
+pub fn parse_params_file(s: &str) -> Result<Vec<(String, String)>, ParamError> {
+    s.lines()
+        .map(str::trim)
+        .filter(|ln| !ln.is_empty() && !ln.starts_with('#'))
+        .map(parse_assignment)
+        .collect()
+}
+
+/// Reads the params file that sits alongside an input file, if there is one.
+/// For `inputs/test.txt` that is `inputs/test.params`.
+pub fn read_params_file_for(input: &Path) -> Result<Vec<(String, String)>, ParamError> {
+    let path = input.with_extension("params");
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => parse_params_file(&contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(ParamError::Io(path.display().to_string(), e)),
+    }
+}
+
+fn known_names(specs: &[&dyn ParamSpec]) -> String {
+    if specs.is_empty() {
+        return "none".to_string();
+    }
+    specs
+        .iter()
+        .map(|spec| spec.name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Debug, Error)]
+pub enum ParamError {
+    #[error("Expected parameter in the format key=value but found: {0}")]
+    MissingEquals(String),
+    #[error("Unknown parameter {name}, this day accepts: {known}")]
+    UnknownParam { name: String, known: String },
+    #[error("Invalid value {value:?} for parameter {name}: {reason}")]
+    InvalidValue {
+        name: String,
+        value: String,
+        reason: String,
+    },
+    #[error("Failed to read params file {0}")]
+    Io(String, #[source] std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: Param<u32> = Param::new("size", 100, "Size of the thing");
+    const START: Param<u32> = Param::new("start", 50, "Where the thing starts");
+    const SPECS: &[&dyn ParamSpec] = &[&SIZE];
+
+    fn kv(k: &str, v: &str) -> (String, String) {
+        (k.to_string(), v.to_string())
+    }
+
+    #[test]
+    fn test_default_when_not_overridden() {
+        let params = Params::new(SPECS, []).unwrap();
+        assert_eq!(params.get(&SIZE), 100);
+    }
+
+    #[test]
+    fn test_override() {
+        let params = Params::new(SPECS, [kv("size", "60"), kv("size", "360")]).unwrap();
+        assert_eq!(params.get(&SIZE), 360);
+    }
+
+    #[test]
+    fn test_unknown_param() {
+        let result = Params::new(SPECS, [kv("nope", "1")]);
+        assert!(matches!(result, Err(ParamError::UnknownParam { .. })));
+    }
+
+    #[test]
+    fn test_undeclared_param_uses_default() {
+        let params = Params::new(SPECS, []).unwrap();
+        assert_eq!(params.get(&START), 50);
+    }
+
+    #[test]
+    fn test_invalid_value() {
+        let result = Params::new(SPECS, [kv("size", "-4")]);
+        assert!(matches!(result, Err(ParamError::InvalidValue { .. })));
+    }
+
+    #[test]
+    fn test_parse_assignment() {
+        assert_eq!(parse_assignment("n_pairs=10").unwrap(), kv("n_pairs", "10"));
+        assert_eq!(parse_assignment(" a = b ").unwrap(), kv("a", "b"));
+        assert!(parse_assignment("n_pairs").is_err());
+        assert!(parse_assignment("=10").is_err());
+    }
+
+    #[test]
+    fn test_parse_params_file() {
+        let file = "# Test input uses fewer pairs\nn_pairs=10\n\nlength = 2\n";
+        let expected = vec![kv("n_pairs", "10"), kv("length", "2")];
+        assert_eq!(parse_params_file(file).unwrap(), expected);
+    }
+}
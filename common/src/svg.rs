@@ -0,0 +1,101 @@
+//! A small SVG writer for the days that can draw their puzzle with `--visualize`.
+//!
+//! Shapes are given in the puzzle's own coordinates and the `viewBox` scales them to fit, so lines are drawn with
+//! `non-scaling-stroke` to keep them visible however large the coordinates get.
+
+use std::fmt::{Display, Write};
+
+/// How wide the picture is drawn, in pixels. The height follows from the aspect ratio.
+const DRAWN_WIDTH: f64 = 600.0;
+
+/// An SVG picture that shapes are added to one at a time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Svg {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    body: String,
+}
+impl Svg {
+    /// A picture showing the area from `(x, y)` to `(x + width, y + height)`.
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self {
+            x,
+            y,
+            width: width.max(f64::MIN_POSITIVE),
+            height: height.max(f64::MIN_POSITIVE),
+            body: String::new(),
+        }
+    }
+    /// Adds a filled rectangle.
+    pub fn rect(mut self, x: f64, y: f64, width: f64, height: f64, fill: &str) -> Self {
+        let _ = writeln!(
+            self.body,
+            r#"<rect x="{x}" y="{y}" width="{width}" height="{height}" fill="{fill}"/>"#
+        );
+        self
+    }
+    /// Adds a filled circle.
+    pub fn circle(mut self, x: f64, y: f64, radius: f64, fill: &str) -> Self {
+        let _ = writeln!(
+            self.body,
+            r#"<circle cx="{x}" cy="{y}" r="{radius}" fill="{fill}"/>"#
+        );
+        self
+    }
+    /// Adds a straight line, `width` pixels wide.
+    pub fn line(mut self, from: (f64, f64), to: (f64, f64), stroke: &str, width: f64) -> Self {
+        let _ = writeln!(
+            self.body,
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{stroke}" stroke-width="{width}" vector-effect="non-scaling-stroke"/>"#,
+            from.0, from.1, to.0, to.1
+        );
+        self
+    }
+    /// Adds a closed polygon through `points`, outlined one pixel wide.
+    pub fn polygon(mut self, points: &[(f64, f64)], fill: &str, stroke: &str) -> Self {
+        let points = points
+            .iter()
+            .map(|(x, y)| format!("{x},{y}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let _ = writeln!(
+            self.body,
+            r#"<polygon points="{points}" fill="{fill}" stroke="{stroke}" stroke-width="1" vector-effect="non-scaling-stroke"/>"#
+        );
+        self
+    }
+}
+impl Display for Svg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let drawn_height = DRAWN_WIDTH * self.height / self.width;
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{DRAWN_WIDTH}" height="{drawn_height:.0}">"#,
+            self.x, self.y, self.width, self.height
+        )?;
+        write!(f, "{}</svg>", self.body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg() {
+        let svg = Svg::new(0.0, 0.0, 4.0, 2.0)
+            .rect(0.0, 0.0, 1.0, 1.0, "red")
+            .circle(2.0, 1.0, 0.5, "blue")
+            .line((0.0, 0.0), (4.0, 2.0), "black", 2.0)
+            .polygon(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)], "none", "green");
+        let expected = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 4 2" width="600" height="300">
+<rect x="0" y="0" width="1" height="1" fill="red"/>
+<circle cx="2" cy="1" r="0.5" fill="blue"/>
+<line x1="0" y1="0" x2="4" y2="2" stroke="black" stroke-width="2" vector-effect="non-scaling-stroke"/>
+<polygon points="0,0 1,0 1,1" fill="none" stroke="green" stroke-width="1" vector-effect="non-scaling-stroke"/>
+</svg>"#;
+        assert_eq!(svg.to_string(), expected);
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{DialConfig, DialError, Direction, Instruction};
+
+/// What to count for each start position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountRule {
+    /// Instructions that leave the dial pointing at a target, as in part 1.
+    Landings,
+    /// Clicks that leave the dial pointing at a target, as in part 2.
+    Crossings,
+}
+
+/// The count for every possible start position of a dial.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StartAnalysis {
+    /// The count when starting at each position, indexed by start position.
+    pub histogram: Vec<u128>,
+    /// The start position with the highest count. The lowest such position if there is a tie.
+    pub argmax: u32,
+}
+impl StartAnalysis {
+    /// Every start position that gives exactly `count`.
+    pub fn starts_with_count(&self, count: u128) -> Vec<u32> {
+        (0..self.histogram.len() as u32)
+            .filter(|&start| self.histogram[start as usize] == count)
+            .collect()
+    }
+    pub fn max(&self) -> u128 {
+        self.histogram[self.argmax as usize]
+    }
+}
+
+/// Works out the count for every start position at once, ignoring the start in `config`.
+///
+/// Starting one position further round moves every position along the way one further round too, so rather
+/// than simulating each start we track where the dial is relative to the start. After the first `k`
+/// instructions it is at `start + offset`, and it lands on target `t` exactly when `start = t - offset`.
+/// The clicks of a single instruction are a number of full turns, which hit every target whatever the start,
+/// plus a partial arc which hits a target for one contiguous run of start positions.
+///
+/// Moving a target one position round moves its run of start positions one round too, so the runs are only
+/// worked out for a target at 0 and then shifted round to each real target at the end.
+/// That makes the whole analysis O(instructions + size * targets), which is O(instructions + size) for the
+/// usual single target.
+pub fn analyse_starts(
+    instructions: &[Instruction],
+    config: &DialConfig,
+    rule: CountRule,
+) -> Result<StartAnalysis, DialError> {
+    // Validates the size and targets the same way the simulation does.
+    DialConfig {
+        start: 0,
+        ..config.clone()
+    }
+    .validate()?;
+    // Positions are widened so that adding two of them can't overflow on the largest dials.
+    let size = config.size as u64;
+    let targets = config.unique_targets();
+
+    // `diff` is a difference array over start positions for a target at 0, with one extra slot for ranges
+    // that end at the top.
+    let mut diff = vec![0i64; size as usize + 1];
+    let mut everywhere = 0u128;
+    let mut add_range = |first: u64, len: u64| {
+        if len == 0 {
+            return;
+        }
+        let end = first + len;
+        diff[first as usize] += 1;
+        if end <= size {
+            diff[end as usize] -= 1;
+        } else {
+            // The range wraps round past the top of the dial.
+            diff[size as usize] -= 1;
+            diff[0] += 1;
+            diff[(end - size) as usize] -= 1;
+        }
+    };
+
+    // How far round the dial has moved from the start, so far.
+    let mut offset = 0u64;
+    for instruction in instructions {
+        let turns = instruction.steps / size;
+        let partial = instruction.steps % size;
+        let next_offset = match instruction.direction {
+            Direction::Clockwise => (offset + partial) % size,
+            Direction::AntiClockwise => (offset + size - partial) % size,
+        };
+        // The start position that puts the dial on the target at the current offset.
+        let on_target = (size - offset) % size;
+        match rule {
+            CountRule::Landings => add_range((size - next_offset) % size, 1),
+            CountRule::Crossings => {
+                everywhere += turns as u128 * targets.len() as u128;
+                // The arc covers the clicks 1..=partial away from the current position. Going clockwise
+                // the target is hit when it's that many clicks ahead of the dial, so the start is behind.
+                let first = match instruction.direction {
+                    Direction::Clockwise => (on_target + size - partial) % size,
+                    Direction::AntiClockwise => (on_target + 1) % size,
+                };
+                add_range(first, partial);
+            }
+        }
+        offset = next_offset;
+    }
+
+    // The count for each start position with a single target at 0.
+    let mut running = 0i64;
+    let at_zero = diff[..size as usize]
+        .iter()
+        .map(|delta| {
+            running += delta;
+            running as u128
+        })
+        .collect::<Vec<_>>();
+    // A target at `t` hits from `start` exactly when a target at 0 would hit from `start - t`.
+    let mut histogram = vec![everywhere; size as usize];
+    for &target in &targets {
+        let target = target as usize;
+        for (start, count) in histogram.iter_mut().enumerate() {
+            *count += at_zero[(start + size as usize - target) % size as usize];
+        }
+    }
+    // `max_by_key` picks the last maximum, so search from the top down to get the lowest position.
+    let argmax = (0..config.size)
+        .rev()
+        .max_by_key(|&start| histogram[start as usize])
+        .unwrap_or_default();
+    Ok(StartAnalysis { histogram, argmax })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Dial, parse_instructions};
+
+    fn example_instructions() -> Vec<Instruction> {
+        parse_instructions(include_str!("../inputs/test.txt").lines())
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    /// Runs the full simulation once per start position.
+    fn brute_force(
+        instructions: &[Instruction],
+        config: &DialConfig,
+        rule: CountRule,
+    ) -> Vec<u128> {
+        (0..config.size)
+            .map(|start| {
+                let mut dial = Dial::new(&DialConfig {
+                    start,
+                    ..config.clone()
+                })
+                .unwrap();
+                dial.events(instructions.iter().copied())
+                    .map(|event| match rule {
+                        CountRule::Landings => event.landings as u128,
+                        CountRule::Crossings => event.crossings,
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_example_start() {
+        let instructions = example_instructions();
+        let config = DialConfig::default();
+        let landings = analyse_starts(&instructions, &config, CountRule::Landings).unwrap();
+        let crossings = analyse_starts(&instructions, &config, CountRule::Crossings).unwrap();
+        assert_eq!(landings.histogram[50], 3);
+        assert_eq!(crossings.histogram[50], 6);
+        assert_eq!(landings.max(), 3);
+        assert!(landings.starts_with_count(3).contains(&50));
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let instructions = example_instructions();
+        let configs = [
+            DialConfig::default(),
+            DialConfig {
+                size: 60,
+                start: 0,
+                targets: vec![0, 15, 45],
+            },
+            DialConfig {
+                size: 7,
+                start: 0,
+                targets: vec![3],
+            },
+            DialConfig {
+                size: 7,
+                start: 0,
+                targets: vec![6, 2],
+            },
+        ];
+        for config in configs {
+            for rule in [CountRule::Landings, CountRule::Crossings] {
+                let analysis = analyse_starts(&instructions, &config, rule).unwrap();
+                assert_eq!(
+                    analysis.histogram,
+                    brute_force(&instructions, &config, rule),
+                    "size {} {rule:?}",
+                    config.size
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_argmax_picks_lowest_start() {
+        // A full turn passes the target once wherever it starts, so every start ties.
+        let instructions = vec!["R10".parse().unwrap()];
+        let config = DialConfig {
+            size: 10,
+            start: 0,
+            targets: vec![4],
+        };
+        let analysis = analyse_starts(&instructions, &config, CountRule::Crossings).unwrap();
+        assert_eq!(analysis.histogram, vec![1; 10]);
+        assert_eq!(analysis.argmax, 0);
+    }
+}
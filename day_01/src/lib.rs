@@ -1,12 +1,698 @@
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    num::{IntErrorKind, ParseIntError},
+    str::FromStr,
+};
+
 use anyhow::{Context, Result};
+use common::explain::{self, Event};
+pub use common::input::Normalization;
+pub use common::params::Params;
+use common::params::{Param, ParamSpec};
+use thiserror::Error;
 
+mod analysis;
 mod part_1;
 mod part_2;
+mod program;
+mod runner;
+mod stack;
+mod trace;
+
+pub use analysis::{CountRule, StartAnalysis, analyse_starts};
+pub use program::{Program, ProgramError, ProgramErrorKind, Statement};
+pub use runner::{DialRunner, Progress, RunnerError};
+pub use stack::DialStack;
+pub use trace::{TraceFormat, TraceRow, render_ring, trace, write_trace};
+
+/// How the input is cleaned up before it is parsed.
+pub const NORMALIZATION: Normalization = Normalization::Full;
+
+/// The initial position of the pointer.
+pub const STARTING_POSITION: Param<u32> =
+    Param::new("start", 50, "Initial position of the dial's pointer");
+/// The upper bound of the pointer, which is also the number of positions on the dial.
+pub const UPPER_BOUND: Param<u32> = Param::new("size", 100, "Number of positions on the dial");
+/// The positions to check against for determining the password.
+pub const TARGETS: Param<Targets> = Param::new(
+    "targets",
+    Targets(Cow::Borrowed(&[0])),
+    "Comma separated dial positions that count towards the password",
+);
+pub const PARAMS: &[&dyn ParamSpec] = &[&STARTING_POSITION, &UPPER_BOUND, &TARGETS];
+
+pub fn run_part_1(input: &str, params: &Params) -> Result<u128> {
+    part_1::run(input, params).context("Failed to run part 1")
+}
+
+pub fn run_part_2(input: &str, params: &Params) -> Result<u128> {
+    part_2::run(input, params).context("Failed to run part 2")
+}
+
+/// Runs part 1 one line at a time, for inputs too big to load all at once.
+pub fn stream_part_1<'a>(lines: impl Iterator<Item = &'a str>, params: &Params) -> Result<u128> {
+    part_1::run_streaming(lines, params).context("Failed to run part 1")
+}
+
+/// Runs part 2 one line at a time, for inputs too big to load all at once.
+pub fn stream_part_2<'a>(lines: impl Iterator<Item = &'a str>, params: &Params) -> Result<u128> {
+    part_2::run_streaming(lines, params).context("Failed to run part 2")
+}
+
+#[derive(Debug, Error)]
+pub enum DialError {
+    #[error("The dial must have at least one position")]
+    EmptyDial,
+    #[error("Starting position {start} is not on a dial with {size} positions")]
+    StartOutOfRange { start: u32, size: u32 },
+    #[error("Target {target} is not on a dial with {size} positions")]
+    TargetOutOfRange { target: u32, size: u32 },
+    #[error("A stack of dials must have at least one dial")]
+    EmptyStack,
+}
+
+/// The list of target positions, as passed to `--param targets=0,25,50`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Targets(pub Cow<'static, [u32]>);
+impl Display for Targets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let targets = self.0.iter().map(u32::to_string).collect::<Vec<_>>();
+        write!(f, "{}", targets.join(","))
+    }
+}
+impl FromStr for Targets {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let targets = s
+            .split(',')
+            .map(|target| target.trim().parse())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Targets(Cow::Owned(targets)))
+    }
+}
+
+/// The shape of a dial and where it starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialConfig {
+    /// The number of positions on the dial, numbered `0..size`.
+    pub size: u32,
+    /// Where the pointer starts.
+    pub start: u32,
+    /// The positions that count towards the password. Listing a target more than once counts it once.
+    pub targets: Vec<u32>,
+}
+impl Default for DialConfig {
+    fn default() -> Self {
+        DialConfig {
+            size: UPPER_BOUND.default,
+            start: STARTING_POSITION.default,
+            targets: TARGETS.default.0.to_vec(),
+        }
+    }
+}
+impl DialConfig {
+    pub fn from_params(params: &Params) -> Self {
+        DialConfig {
+            size: params.get(&UPPER_BOUND),
+            start: params.get(&STARTING_POSITION),
+            targets: params.get(&TARGETS).0.into_owned(),
+        }
+    }
+    /// Checks that the start and every target are actually on the dial.
+    pub fn validate(&self) -> Result<(), DialError> {
+        let size = self.size;
+        if size == 0 {
+            return Err(DialError::EmptyDial);
+        }
+        if self.start >= size {
+            return Err(DialError::StartOutOfRange {
+                start: self.start,
+                size,
+            });
+        }
+        if let Some(&target) = self.targets.iter().find(|&&target| target >= size) {
+            return Err(DialError::TargetOutOfRange { target, size });
+        }
+        Ok(())
+    }
+    /// The targets, sorted and with duplicates removed.
+    pub(crate) fn unique_targets(&self) -> Vec<u32> {
+        let mut targets = self.targets.clone();
+        targets.sort_unstable();
+        targets.dedup();
+        targets
+    }
+}
+
+/// A dial with positions `0..size`, turned one instruction at a time.
+/// Keeps a running tally of how often each target has been landed on and crossed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dial {
+    /// The current position on the dial between 0 and the size.
+    position: u32,
+    /// The number of positions on the dial.
+    size: u32,
+    /// One tally per target, sorted by target.
+    hits: Vec<TargetHits>,
+}
+impl Dial {
+    pub fn new(config: &DialConfig) -> Result<Self, DialError> {
+        config.validate()?;
+        Ok(Dial {
+            position: config.start,
+            size: config.size,
+            hits: config
+                .unique_targets()
+                .into_iter()
+                .map(TargetHits::new)
+                .collect(),
+        })
+    }
+    /// Creates a dial set up according to the params.
+    pub fn from_params(params: &Params) -> Result<Self, DialError> {
+        Dial::new(&DialConfig::from_params(params))
+    }
+    pub fn position(&self) -> u32 {
+        self.position
+    }
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+    /// How often each target has been landed on and crossed so far, sorted by target.
+    pub fn hits(&self) -> &[TargetHits] {
+        &self.hits
+    }
+    /// Adds on whatever has been hit since `since`, another `times` times over.
+    /// Returns `None`, leaving the hits as they were, if any of them would overflow.
+    pub(crate) fn extrapolate_hits(&mut self, since: &[TargetHits], times: u64) -> Option<()> {
+        let times = times as u128;
+        let extrapolate = |now: u128, then: u128| (now - then).checked_mul(times)?.checked_add(now);
+        let hits = self
+            .hits
+            .iter()
+            .zip(since)
+            .map(|(hits, since)| {
+                Some(TargetHits {
+                    target: hits.target,
+                    landings: extrapolate(hits.landings, since.landings)?,
+                    crossings: extrapolate(hits.crossings, since.crossings)?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        self.hits = hits;
+        Some(())
+    }
+    /// Moves the dial straight to `position` and adds `added` on to the hits, as if it had turned there.
+    /// Returns `None`, leaving the dial as it was, if any of the hits would overflow.
+    pub(crate) fn skip_to(&mut self, position: u32, added: &[TargetHits]) -> Option<()> {
+        let hits = self
+            .hits
+            .iter()
+            .zip(added)
+            .map(|(hits, added)| {
+                Some(TargetHits {
+                    target: hits.target,
+                    landings: hits.landings.checked_add(added.landings)?,
+                    crossings: hits.crossings.checked_add(added.crossings)?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        self.hits = hits;
+        self.position = position;
+        Some(())
+    }
+    /// Turns the dial according to a single instruction and describes what happened on the way.
+    pub fn step(&mut self, instruction: Instruction) -> DialEvent {
+        let Instruction { direction, steps } = instruction;
+        let from = self.position;
+        // Whole turns don't change where the dial ends up.
+        // Worked out in u64 since adding up two positions can overflow u32 on a big enough dial.
+        let size = self.size as u64;
+        let partial = steps % size;
+        let position = match direction {
+            Direction::Clockwise => (from as u64 + partial) % size,
+            Direction::AntiClockwise => (from as u64 + size - partial) % size,
+        } as u32;
+        let mut landings = 0;
+        let mut crossings = 0;
+        for hits in &mut self.hits {
+            let landed = (position == hits.target) as u32;
+            let crossed = passes(self.size, from, hits.target, instruction);
+            // Saturating, since a single target can't go past the tally's totals and those are checked.
+            hits.landings = hits.landings.saturating_add(landed as u128);
+            hits.crossings = hits.crossings.saturating_add(crossed as u128);
+            landings += landed;
+            crossings += crossed as u128;
+        }
+        // Going clockwise the dial wraps when it moves onto 0, anticlockwise when it moves onto the top.
+        let wraps = match direction {
+            Direction::Clockwise => passes(self.size, from, 0, instruction),
+            Direction::AntiClockwise => passes(self.size, from, self.size - 1, instruction),
+        };
+        let event = DialEvent {
+            from,
+            position,
+            landings,
+            crossings,
+            wraps,
+        };
+        explain::record(|| {
+            Event::new("dial_instruction")
+                .with("instruction", instruction.to_string())
+                .with("from", event.from)
+                .with("to", event.position)
+                .with("crossings", event.crossings)
+        });
+        self.position = position;
+        event
+    }
+    /// Turns the dial through every instruction in turn, yielding what happened at each one.
+    pub fn events<I>(&mut self, instructions: I) -> impl Iterator<Item = DialEvent>
+    where
+        I: IntoIterator<Item = Instruction>,
+    {
+        instructions
+            .into_iter()
+            .map(|instruction| self.step(instruction))
+    }
+}
+
+/// The number of clicks of the instruction, starting from `from`, that leave the dial pointing at `target`.
+fn passes(size: u32, from: u32, target: u32, instruction: Instruction) -> u64 {
+    // How many clicks it takes to first reach the target, a full turn if we start on it.
+    let (size, from, target) = (size as u64, from as u64, target as u64);
+    let distance = match instruction.direction {
+        Direction::Clockwise => (target + size - from) % size,
+        Direction::AntiClockwise => (from + size - target) % size,
+    };
+    let first = if distance == 0 { size } else { distance };
+    if instruction.steps < first {
+        0
+    } else {
+        // After the first time, the target comes round again once every full turn.
+        // Subtracting first means this can't overflow, even for the largest possible number of steps.
+        (instruction.steps - first) / size + 1
+    }
+}
+
+/// Running totals for a single target position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TargetHits {
+    pub target: u32,
+    /// The number of instructions that left the dial pointing at the target.
+    pub landings: u128,
+    /// The number of clicks that left the dial pointing at the target, mid turn or not.
+    pub crossings: u128,
+}
+impl TargetHits {
+    fn new(target: u32) -> Self {
+        TargetHits {
+            target,
+            landings: 0,
+            crossings: 0,
+        }
+    }
+}
+
+/// What happened during a single turn of the dial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DialEvent {
+    /// Where the dial was before the turn.
+    pub from: u32,
+    /// Where the dial is after the turn.
+    pub position: u32,
+    /// 1 if the dial was left pointing at a target, otherwise 0.
+    pub landings: u32,
+    /// The number of clicks during the turn that left the dial pointing at a target, including the last click.
+    /// Summed over all targets.
+    pub crossings: u128,
+    /// The number of times the dial went past the end of its range and round to the other end.
+    pub wraps: u64,
+}
+
+/// The totals of a run of dial events.
+/// Kept as `u128` since a run of `u64` sized turns can pass a target more than `u64::MAX` times.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub landings: u128,
+    pub crossings: u128,
+    pub wraps: u128,
+}
+impl Tally {
+    pub fn add(&mut self, event: DialEvent) {
+        self.landings += event.landings as u128;
+        self.crossings += event.crossings;
+        self.wraps += event.wraps as u128;
+    }
+    /// Adds the event on, or returns `None` if any of the totals would overflow.
+    pub fn checked_add(&self, event: DialEvent) -> Option<Tally> {
+        Some(Tally {
+            landings: self.landings.checked_add(event.landings as u128)?,
+            crossings: self.crossings.checked_add(event.crossings)?,
+            wraps: self.wraps.checked_add(event.wraps as u128)?,
+        })
+    }
+    /// Adds another tally on, or returns `None` if any of the totals would overflow.
+    pub(crate) fn checked_add_tally(&self, other: &Tally) -> Option<Tally> {
+        Some(Tally {
+            landings: self.landings.checked_add(other.landings)?,
+            crossings: self.crossings.checked_add(other.crossings)?,
+            wraps: self.wraps.checked_add(other.wraps)?,
+        })
+    }
+    /// The tally with whatever has been added since `since` added on another `times` times over,
+    /// or `None` if any of the totals would overflow.
+    pub(crate) fn extrapolated(&self, since: &Tally, times: u64) -> Option<Tally> {
+        let times = times as u128;
+        let extrapolate = |now: u128, then: u128| (now - then).checked_mul(times)?.checked_add(now);
+        Some(Tally {
+            landings: extrapolate(self.landings, since.landings)?,
+            crossings: extrapolate(self.crossings, since.crossings)?,
+            wraps: extrapolate(self.wraps, since.wraps)?,
+        })
+    }
+}
 
-pub fn run_part_1(input: &str) -> Result<u64> {
-    part_1::run(input).context("Failed to run part 1")
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub direction: Direction,
+    pub steps: u64,
+}
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.direction, self.steps)
+    }
 }
+#[cfg(test)]
+impl Instruction {
+    fn l(steps: u64) -> Self {
+        Instruction {
+            direction: Direction::AntiClockwise,
+            steps,
+        }
+    }
+    fn r(steps: u64) -> Self {
+        Instruction {
+            direction: Direction::Clockwise,
+            steps,
+        }
+    }
+}
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Split the string into its two components.
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ParseError::InvalidDirection(s.to_string()));
+        }
+        let (dir, distance) = s.split_at(s.ceil_char_boundary(1));
+        let direction = dir.parse()?;
+        let distance = distance
+            .parse()
+            .map_err(|e: ParseIntError| match e.kind() {
+                IntErrorKind::PosOverflow => ParseError::DistanceTooLarge(distance.to_string()),
+                _ => ParseError::NotANumber(distance.to_string()),
+            })?;
+        Ok(Instruction {
+            direction,
+            steps: distance,
+        })
+    }
+}
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Direction {
+    Clockwise,
+    AntiClockwise,
+}
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::Clockwise => write!(f, "R"),
+            Direction::AntiClockwise => write!(f, "L"),
+        }
+    }
+}
+impl FromStr for Direction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "L" => Ok(Direction::AntiClockwise),
+            "R" => Ok(Direction::Clockwise),
+            _ => Err(ParseError::InvalidDirection(s.to_string())),
+        }
+    }
+}
+#[derive(Debug, Error)]
+pub enum ParseError {
+    #[error("Invalid direction, expected 'L' or 'R', got {0}")]
+    InvalidDirection(String),
+    #[error("Invalid distance, expected a positive integer but found {0:?}")]
+    NotANumber(String),
+    #[error("Distance {0} is too large, a single instruction can turn the dial at most {max} clicks", max = u64::MAX)]
+    DistanceTooLarge(String),
+}
+
+/// Parses one instruction per line, skipping blank lines.
+pub fn parse_instructions<'a>(
+    lines: impl Iterator<Item = &'a str>,
+) -> impl Iterator<Item = Result<Instruction, ParseError>> {
+    lines
+        .filter(|ln| !ln.trim().is_empty())
+        .map(str::parse::<Instruction>)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dial_config(size: u32, start: u32, targets: &[u32]) -> DialConfig {
+        DialConfig {
+            size,
+            start,
+            targets: targets.to_vec(),
+        }
+    }
+
+    fn example_instructions() -> Vec<Instruction> {
+        vec![
+            Instruction::l(68),
+            Instruction::l(30),
+            Instruction::r(48),
+            Instruction::l(5),
+            Instruction::r(60),
+            Instruction::l(55),
+            Instruction::l(1),
+            Instruction::l(99),
+            Instruction::r(14),
+            Instruction::l(82),
+        ]
+    }
+
+    #[test]
+    fn test_parse_happy_path() {
+        let input = "L23\nR45 \n\nL9";
+        let parsed = parse_instructions(input.lines())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            parsed,
+            vec![Instruction::l(23), Instruction::r(45), Instruction::l(9)]
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(matches!(
+            "X10".parse::<Instruction>(),
+            Err(ParseError::InvalidDirection(_))
+        ));
+        assert!(matches!(
+            "L1O".parse::<Instruction>(),
+            Err(ParseError::NotANumber(_))
+        ));
+        assert!(matches!(
+            "R-5".parse::<Instruction>(),
+            Err(ParseError::NotANumber(_))
+        ));
+        assert!(matches!(
+            "R18446744073709551616".parse::<Instruction>(),
+            Err(ParseError::DistanceTooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn test_step_positions() {
+        let mut dial = Dial::new(&DialConfig::default()).unwrap();
+        let positions = dial
+            .events(example_instructions())
+            .map(|event| event.position)
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![82, 52, 0, 95, 55, 0, 99, 0, 14, 32]);
+    }
+
+    #[test]
+    fn test_step_zero_landings() {
+        let mut dial = Dial::new(&DialConfig::default()).unwrap();
+        let landings: u32 = dial
+            .events(example_instructions())
+            .map(|event| event.landings)
+            .sum();
+        assert_eq!(landings, 3);
+    }
+
+    #[test]
+    fn test_step_zero_crossings() {
+        let mut dial = Dial::new(&DialConfig::default()).unwrap();
+        let crossings = dial
+            .events(example_instructions())
+            .map(|event| event.crossings)
+            .collect::<Vec<_>>();
+        assert_eq!(crossings, vec![1, 0, 1, 0, 1, 1, 0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn test_step_crossings_running_total() {
+        let instructions = vec![
+            Instruction::r(50),
+            Instruction::r(50),
+            Instruction::l(50),
+            Instruction::l(50),
+            Instruction::r(75),
+            Instruction::l(50),
+            Instruction::l(25),
+            Instruction::l(75),
+            Instruction::r(50),
+        ];
+        let mut dial = Dial::new(&DialConfig::default()).unwrap();
+        let totals = dial
+            .events(instructions)
+            .scan(0, |total, event| {
+                *total += event.crossings;
+                Some(*total)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(totals, vec![1, 1, 2, 2, 3, 4, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_big_rotation() {
+        let mut dial = Dial::new(&dial_config(100, 0, &[0])).unwrap();
+
+        let event = dial.step(Instruction::r(1000));
+        assert_eq!(event.position, 0);
+        assert_eq!(event.crossings, 10);
+        assert_eq!(event.wraps, 10);
+
+        let event = dial.step(Instruction::l(1000));
+        assert_eq!(event.position, 0);
+        assert_eq!(event.crossings, 10);
+        assert_eq!(event.wraps, 10);
+    }
+
+    #[test]
+    fn test_wraps_differ_from_crossings_anticlockwise() {
+        // Landing on 0 going anticlockwise points at 0 without wrapping round to the top.
+        let mut dial = Dial::new(&dial_config(100, 50, &[0])).unwrap();
+        let event = dial.step(Instruction::l(50));
+        assert_eq!((event.crossings, event.wraps), (1, 0));
+        // Leaving 0 anticlockwise wraps straight away without pointing at 0 again.
+        let event = dial.step(Instruction::l(1));
+        assert_eq!((event.position, event.crossings, event.wraps), (99, 0, 1));
+    }
+
+    #[test]
+    fn test_empty_dial() {
+        assert!(matches!(
+            Dial::new(&dial_config(0, 0, &[0])),
+            Err(DialError::EmptyDial)
+        ));
+        assert!(matches!(
+            Dial::new(&dial_config(60, 60, &[0])),
+            Err(DialError::StartOutOfRange { .. })
+        ));
+        assert!(matches!(
+            Dial::new(&dial_config(60, 0, &[0, 75])),
+            Err(DialError::TargetOutOfRange { target: 75, .. })
+        ));
+    }
+
+    #[test]
+    fn test_60_notch_dial() {
+        let mut dial = Dial::new(&dial_config(60, 30, &[0, 15, 45])).unwrap();
+        // Clockwise 90 clicks from 30: passes 45, 0, 15, 45 and lands on 0.
+        let event = dial.step(Instruction::r(90));
+        assert_eq!((event.position, event.landings, event.crossings), (0, 1, 5));
+        // Anticlockwise 20 clicks from 0: passes 45 on the way down to 40.
+        let event = dial.step(Instruction::l(20));
+        assert_eq!(
+            (event.position, event.landings, event.crossings),
+            (40, 0, 1)
+        );
+        let tally = dial
+            .hits()
+            .iter()
+            .map(|hits| (hits.target, hits.landings, hits.crossings))
+            .collect::<Vec<_>>();
+        assert_eq!(tally, vec![(0, 1, 2), (15, 0, 1), (45, 0, 3)]);
+    }
+
+    #[test]
+    fn test_360_notch_dial() {
+        let mut dial = Dial::new(&dial_config(360, 0, &[90, 270])).unwrap();
+        // Three and a quarter turns anticlockwise from 0 ends on 270, having passed it 4 times and 90 three times.
+        let event = dial.step(Instruction::l(1170));
+        assert_eq!(event.position, 270);
+        assert_eq!((event.landings, event.crossings, event.wraps), (1, 7, 4));
+    }
+
+    #[test]
+    fn test_dial_bigger_than_half_of_u32() {
+        let mut dial = Dial::new(&dial_config(4_000_000_000, 3_999_999_999, &[0])).unwrap();
+        let event = dial.step(Instruction::r(3_000_000_000));
+        assert_eq!(event.position, 2_999_999_999);
+        assert_eq!((event.landings, event.crossings, event.wraps), (0, 1, 1));
+        let event = dial.step(Instruction::l(3_000_000_000));
+        assert_eq!(event.position, 3_999_999_999);
+        assert_eq!((event.landings, event.crossings, event.wraps), (0, 1, 1));
+    }
+
+    #[test]
+    fn test_duplicate_targets_count_once() {
+        let mut dial = Dial::new(&dial_config(100, 50, &[0, 0])).unwrap();
+        let event = dial.step(Instruction::r(50));
+        assert_eq!((event.landings, event.crossings), (1, 1));
+        assert_eq!(dial.hits().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_targets() {
+        let targets = "0, 15,45".parse::<Targets>().unwrap();
+        assert_eq!(targets.0.as_ref(), &[0, 15, 45]);
+        assert_eq!(targets.to_string(), "0,15,45");
+        assert!("0,,1".parse::<Targets>().is_err());
+    }
+
+    #[test]
+    fn test_huge_steps_dont_overflow() {
+        let mut dial = Dial::new(&dial_config(100, 99, &[0])).unwrap();
+        let event = dial.step(Instruction::r(u64::MAX));
+        // u64::MAX = 18446744073709551615, so the dial goes round that many hundreds of times and 15 more clicks.
+        assert_eq!(event.position, 14);
+        assert_eq!(event.crossings, 184467440737095517);
+        let event = dial.step(Instruction::l(u64::MAX));
+        assert_eq!(event.position, 99);
+        assert_eq!(event.crossings, 184467440737095517);
 
-pub fn run_part_2(input: &str) -> Result<u64> {
-    part_2::run(input).context("Failed to run part 2")
+        // On a single position dial every click lands on the target, so the tally needs more than a u64.
+        let mut dial = Dial::new(&dial_config(1, 0, &[0])).unwrap();
+        let mut tally = Tally::default();
+        tally.add(dial.step(Instruction::r(u64::MAX)));
+        tally.add(dial.step(Instruction::r(u64::MAX)));
+        assert_eq!(tally.crossings, 2 * u64::MAX as u128);
+    }
 }
@@ -14,9 +14,38 @@ enum Part {
 struct Args {
     #[command(flatten)]
     verbosity: clap_verbosity_flag::Verbosity,
+    /// The input file, or `-` to read instructions from stdin as they arrive.
     input: std::path::PathBuf,
-    #[arg(short, long, default_value = "Both")]
+    #[arg(short, long, default_value = "both")]
     part: Part,
+    /// Override a puzzle parameter, e.g. `--param size=1000`. Can be repeated.
+    #[arg(long = "param", value_name = "KEY=VALUE", value_parser = common::params::parse_assignment)]
+    params: Vec<(String, String)>,
+    /// Print every step the solution takes. Pass a file to write the steps to it as JSON lines instead.
+    #[arg(long, value_name = "FILE", num_args = 0..=1, require_equals = true, default_missing_value = "-")]
+    explain: Option<std::path::PathBuf>,
+    /// After answering, time each part over this many in-process runs and print the fastest, median and slowest.
+    #[arg(long, value_name = "RUNS", num_args = 0..=1, require_equals = true, default_missing_value = "10")]
+    bench: Option<usize>,
+    /// Memory map the input and stream through it rather than loading it all at once.
+    /// Meant for very large inputs.
+    #[arg(long)]
+    stream: bool,
+    /// Write every instruction's from, to and crossings to a file, as JSON if it ends in `.json` and CSV otherwise.
+    #[arg(long, value_name = "FILE")]
+    trace: Option<std::path::PathBuf>,
+    /// Animate the dial in the terminal before solving, pausing for this many milliseconds on each instruction.
+    #[arg(long, value_name = "MS", num_args = 0..=1, require_equals = true, default_missing_value = "300")]
+    animate: Option<u64>,
+    /// When reading from stdin, print the running totals to stderr after this many instructions. 0 turns it off.
+    #[arg(long, value_name = "N", default_value_t = 1000)]
+    report_every: u64,
+}
+
+/// The input is either loaded up front or memory mapped with `--stream`.
+enum Input {
+    Loaded(String),
+    Mapped(common::input::MappedInput),
 }
 
 fn main() -> Result<()> {
@@ -26,31 +55,193 @@ fn main() -> Result<()> {
     env_logger::Builder::new()
         .filter_level(args.verbosity.into())
         .init();
-    // Read the input file into a string.
-    let input = read_input_file_to_string(&args.input)
-        .with_context(|| format!("Failed to read input file {}", args.input.display()))?;
+    // Collect the puzzle parameters for this run.
+    let params = read_params(&args.input, &args.params).context("Invalid parameters")?;
+    if args.input == std::path::Path::new("-") {
+        start_explaining_if_asked(&args)?;
+        run_stdin(&args, &params)?;
+        return common::explain::finish().context("Failed to write explain output");
+    }
+    // Either read the input file into a string, cleaning up line endings and the like on the way,
+    // or memory map it so that it can be streamed through.
+    let input = if args.stream {
+        common::input::MappedInput::open(&args.input).map(Input::Mapped)
+    } else {
+        common::input::read_normalized(&args.input, solution::NORMALIZATION).map(Input::Loaded)
+    }
+    .with_context(|| format!("Failed to read input file {}", args.input.display()))?;
+    let run_part_1 = || match &input {
+        Input::Loaded(input) => solution::run_part_1(input, &params),
+        Input::Mapped(input) => solution::stream_part_1(input.lines(), &params),
+    };
+    let run_part_2 = || match &input {
+        Input::Loaded(input) => solution::run_part_2(input, &params),
+        Input::Mapped(input) => solution::stream_part_2(input.lines(), &params),
+    };
+
+    // Show the dial's journey if asked to.
+    // This turns a dial of its own, so it happens before recording starts to keep it out of the explain output.
+    if args.trace.is_some() || args.animate.is_some() {
+        // Both need the whole program, so a mapped input is normalized into memory the same way a loaded one is.
+        let normalized = match &input {
+            Input::Loaded(input) => std::borrow::Cow::Borrowed(input.as_str()),
+            Input::Mapped(input) => std::borrow::Cow::Owned(common::input::normalize(
+                input.as_str(),
+                solution::NORMALIZATION,
+            )),
+        };
+        if let Some(path) = &args.trace {
+            write_trace(&normalized, &params, path)
+                .with_context(|| format!("Failed to write trace to {}", path.display()))?;
+        }
+        if let Some(delay) = args.animate {
+            animate(
+                &normalized,
+                &params,
+                std::time::Duration::from_millis(delay),
+            )
+            .context("Failed to animate the dial")?;
+        }
+    }
+    start_explaining_if_asked(&args)?;
 
     // Run the appropriate part(s) of the solution.
     match args.part {
         Part::One => {
-            let part_1 = solution::run_part_1(&input)?;
+            common::explain::start_part(1);
+            let part_1 = run_part_1()?;
             println!("Part 1: {}", part_1);
         }
         Part::Two => {
-            let part_2 = solution::run_part_2(&input)?;
+            common::explain::start_part(2);
+            let part_2 = run_part_2()?;
             println!("Part 2: {}", part_2);
         }
         Part::Both => {
-            let part_1 = solution::run_part_1(&input)?;
-            let part_2 = solution::run_part_2(&input)?;
+            common::explain::start_part(1);
+            let part_1 = run_part_1()?;
+            common::explain::start_part(2);
+            let part_2 = run_part_2()?;
             println!("Part 1: {}", part_1);
             println!("Part 2: {}", part_2);
         }
     }
+    common::explain::finish().context("Failed to write explain output")?;
+    // Time the parts again if asked to, now that nothing is being recorded.
+    if let Some(runs) = args.bench {
+        if matches!(args.part, Part::One | Part::Both) {
+            println!("Part 1 time: {}", common::bench::time(runs, run_part_1));
+        }
+        if matches!(args.part, Part::Two | Part::Both) {
+            println!("Part 2 time: {}", common::bench::time(runs, run_part_2));
+        }
+    }
 
     Ok(())
 }
 
-fn read_input_file_to_string(path: &std::path::Path) -> Result<String, std::io::Error> {
-    std::fs::read_to_string(path)
+/// Builds the parameters for the run. Values come from the params file that sits next to the input
+/// (e.g. `inputs/test.params`) and can then be overridden on the command line.
+fn read_params(
+    input: &std::path::Path,
+    overrides: &[(String, String)],
+) -> Result<solution::Params, common::params::ParamError> {
+    let mut assignments = common::params::read_params_file_for(input)?;
+    assignments.extend(overrides.iter().cloned());
+    solution::Params::new(solution::PARAMS, assignments)
+}
+
+/// Runs over instructions piped in on stdin as they arrive, rather than waiting for the whole input.
+fn run_stdin(args: &Args, params: &solution::Params) -> Result<()> {
+    let dial = solution::Dial::from_params(params)?;
+    let mut runner = solution::DialRunner::new(dial).report_every(args.report_every);
+    let tally = runner.run(std::io::stdin().lock(), |progress| {
+        eprintln!(
+            "After {} instructions the dial is at {}. Part 1: {} Part 2: {}",
+            progress.instructions,
+            progress.position,
+            progress.tally.landings,
+            progress.tally.crossings
+        );
+    })?;
+    if !matches!(args.part, Part::Two) {
+        println!("Part 1: {}", tally.landings);
+    }
+    if !matches!(args.part, Part::One) {
+        println!("Part 2: {}", tally.crossings);
+    }
+    Ok(())
+}
+
+fn program(input: &str) -> Result<solution::Program> {
+    input.parse().context("Failed to parse instructions")
+}
+
+fn write_trace(input: &str, params: &solution::Params, path: &std::path::Path) -> Result<()> {
+    let mut dial = solution::Dial::from_params(params)?;
+    let rows = solution::trace(&mut dial, &program(input)?)?;
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    solution::write_trace(&rows, solution::TraceFormat::for_path(path), file)?;
+    Ok(())
+}
+
+/// Redraws the dial after every turn, with a caption saying what just happened.
+fn animate(input: &str, params: &solution::Params, delay: std::time::Duration) -> Result<()> {
+    use std::io::Write;
+    let program = program(input)?;
+    let mut dial = solution::Dial::from_params(params)?;
+    let mut out = std::io::stdout().lock();
+    let mut turns = 0;
+    let mut crossings = 0u128;
+    // The program has the dial borrowed while it runs, so the drawing follows along on a copy of it.
+    let mut ring = solution::Dial::from_params(params)?;
+    let mut drawn = Ok(());
+    program.run_observed(&mut dial, |instruction, event| {
+        if drawn.is_err() {
+            return;
+        }
+        turns += 1;
+        crossings = crossings.saturating_add(event.crossings);
+        ring.step(instruction);
+        drawn = (|| {
+            // Clear the screen and move the cursor back to the top left.
+            write!(out, "\x1b[2J\x1b[H")?;
+            writeln!(out, "{}\n", solution::render_ring(&ring, 10))?;
+            writeln!(
+                out,
+                "{turns}: {}{} from {} to {}, {} crossings ({crossings} so far)",
+                instruction.direction,
+                instruction.steps,
+                event.from,
+                event.position,
+                event.crossings,
+            )?;
+            out.flush()?;
+            std::thread::sleep(delay);
+            std::io::Result::Ok(())
+        })();
+    })?;
+    Ok(drawn?)
+}
+
+/// Starts recording the steps of the solution if `--explain` was passed.
+fn start_explaining_if_asked(args: &Args) -> Result<()> {
+    if let Some(path) = &args.explain {
+        start_explaining(path)
+            .with_context(|| format!("Failed to create explain file {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Records the steps of the solution, pretty printed to stderr if `path` is `-` or as JSON lines to the file otherwise.
+fn start_explaining(path: &std::path::Path) -> std::io::Result<()> {
+    use common::explain::Sink;
+    let sink = if path == std::path::Path::new("-") {
+        Sink::Pretty(Box::new(std::io::stderr()))
+    } else {
+        let file = std::fs::File::create(path)?;
+        Sink::JsonLines(Box::new(std::io::BufWriter::new(file)))
+    };
+    common::explain::enable(sink);
+    Ok(())
 }
@@ -1,118 +1,36 @@
-use std::{num::ParseIntError, str::FromStr};
 use thiserror::Error;
 
-/// The initial position of the pointer.
-const STARTING_POSITION: u32 = 50;
-/// The upper bound of the pointer.
-const UPPER_BOUND: u32 = 100;
-/// The lower bound of the pointer.
-// const LOWER_BOUND: u32 = 0;
-/// The value to check against for determining the password.
-const MAGIC_NUMBER: u32 = 0;
+use crate::{
+    Dial, DialError, Params, ParseError, Program, ProgramError, Tally, parse_instructions,
+};
 
 #[derive(Debug, Error)]
 pub enum PartError {
     #[error(transparent)]
     ParseError(#[from] ParseError),
+    #[error(transparent)]
+    InvalidProgram(#[from] ProgramError),
+    #[error(transparent)]
+    DialError(#[from] DialError),
 }
-pub fn run(input: &str) -> Result<u64, PartError> {
-    let mut dial = input.parse::<Dial>()?;
-    let mut counter = 0;
-    while !dial.is_complete() {
-        dial.apply_instruction();
-        if dial.position == MAGIC_NUMBER {
-            counter += 1;
-        }
-    }
-
-    Ok(counter)
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct Dial {
-    /// The current position on the dial between the Lower Bound and Upper Bound.
-    position: u32,
-    /// The current index of the instruction being executed.
-    index: usize,
-    /// The instructions to execute.
-    instructions: Vec<Instruction>,
-}
-impl Dial {
-    fn is_complete(&self) -> bool {
-        self.index >= self.instructions.len()
-    }
-    fn apply_instruction(&mut self) {
-        let instruction = self.instructions[self.index];
-        let new_position = match instruction.direction {
-            Direction::Clockwise => (self.position + instruction.steps) % UPPER_BOUND,
-            Direction::AntiClockwise => ((self.position + UPPER_BOUND)
-                - (instruction.steps % UPPER_BOUND))
-                .rem_euclid(UPPER_BOUND),
-        };
-        self.position = new_position;
-        self.index += 1;
-    }
-}
-impl FromStr for Dial {
-    type Err = ParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Get a list of instructions from the input string.
-        let instructions = s
-            .trim()
-            .lines()
-            .map(|ln| ln.parse::<Instruction>())
-            .collect::<Result<Vec<_>, _>>()?;
-        // Create a `Dial` struct with default values.
-        let dial = Dial {
-            position: STARTING_POSITION,
-            index: 0,
-            instructions,
-        };
-        Ok(dial)
-    }
-}
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-struct Instruction {
-    direction: Direction,
-    steps: u32,
-}
-impl FromStr for Instruction {
-    type Err = ParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Split the string into its two components.
-        let (dir, distance) = s.trim().split_at(1);
-        let direction = dir.parse()?;
-        let distance = distance.parse()?;
-        Ok(Instruction {
-            direction,
-            steps: distance,
-        })
-    }
-}
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Direction {
-    Clockwise,
-    AntiClockwise,
+/// The password is the number of times the dial is left pointing at a target.
+pub fn run(input: &str, params: &Params) -> Result<u128, PartError> {
+    let program = input.parse::<Program>()?;
+    let mut dial = Dial::from_params(params)?;
+    Ok(program.run(&mut dial)?.landings)
 }
-impl FromStr for Direction {
-    type Err = ParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "L" => Ok(Direction::AntiClockwise),
-            "R" => Ok(Direction::Clockwise),
-            _ => Err(ParseError::InvalidDirection(s.to_string())),
-        }
+/// Same as `run`, but each instruction is applied as soon as it is parsed instead of collecting them all first.
+/// Only plain `L<n>` and `R<n>` instructions are understood, one per line. Blank lines are skipped.
+pub fn run_streaming<'a>(
+    lines: impl Iterator<Item = &'a str>,
+    params: &Params,
+) -> Result<u128, PartError> {
+    let mut dial = Dial::from_params(params)?;
+    let mut tally = Tally::default();
+    for instruction in parse_instructions(lines) {
+        tally.add(dial.step(instruction?));
     }
-}
-#[derive(Debug, Error)]
-pub enum ParseError {
-    #[error("Invalid direction, expected 'L' or 'R', got {0}")]
-    InvalidDirection(String),
-    #[error("Invalid distance, expected a positive integer: {0}")]
-    InvalidDistance(#[from] ParseIntError),
+    Ok(tally.landings)
 }
 
 #[cfg(test)]
@@ -120,52 +38,10 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_parse_happy_path() {
-        let input = "L23\nR45 \nL9";
-        let parsed = input.parse::<Dial>().unwrap();
-        let expected_instructions = vec![
-            Instruction {
-                direction: Direction::AntiClockwise,
-                steps: 23,
-            },
-            Instruction {
-                direction: Direction::Clockwise,
-                steps: 45,
-            },
-            Instruction {
-                direction: Direction::AntiClockwise,
-                steps: 9,
-            },
-        ];
-        assert_eq!(parsed.instructions, expected_instructions);
-    }
-
-    #[test]
-    fn test_apply_instruction() {
-        let mut dial = Dial {
-            position: STARTING_POSITION,
-            index: 0,
-            instructions: vec![
-                Instruction {
-                    direction: Direction::AntiClockwise,
-                    steps: 68,
-                },
-                Instruction {
-                    direction: Direction::AntiClockwise,
-                    steps: 30,
-                },
-                Instruction {
-                    direction: Direction::Clockwise,
-                    steps: 48,
-                },
-            ],
-        };
-
-        dial.apply_instruction();
-        assert_eq!(dial.position, 82);
-        dial.apply_instruction();
-        assert_eq!(dial.position, 52);
-        dial.apply_instruction();
-        assert_eq!(dial.position, 0);
+    fn test_run_streaming_matches_run() {
+        let input = include_str!("../inputs/test.txt");
+        let params = Params::default();
+        assert_eq!(run(input, &params).unwrap(), 3);
+        assert_eq!(run_streaming(input.lines(), &params).unwrap(), 3);
     }
 }
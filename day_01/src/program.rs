@@ -0,0 +1,756 @@
+//! The extended instruction language.
+//!
+//! On top of the plain `L<n>` and `R<n>` turns a program can contain:
+//! - `=<pos>` to turn the dial to an absolute position, whichever way round is shorter (clockwise on a tie).
+//! - `x<k>( ... )` to repeat a block of instructions `k` times. Blocks can be nested.
+//! - `#` comments that run to the end of the line.
+//!
+//! Instructions are separated by whitespace, so a whole program can be written on one line.
+
+use std::{fmt::Display, ops::Range, str::FromStr};
+
+use common::explain::{self, Event};
+use thiserror::Error;
+
+use crate::{Dial, DialEvent, Direction, Instruction, Tally, TargetHits};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Statement {
+    Turn {
+        instruction: Instruction,
+        span: Range<usize>,
+    },
+    /// Turn the dial to this position the shortest way round.
+    Set { position: u32, span: Range<usize> },
+    Repeat {
+        count: u64,
+        body: Vec<Statement>,
+        /// Where the `x<k>` is, since skipping its cycles is what can overflow.
+        span: Range<usize>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    pub statements: Vec<Statement>,
+}
+impl Program {
+    /// Runs the program on the dial and totals up everything that happened.
+    ///
+    /// Repeats are not unrolled. A repeat of plain turns is worked out in closed form, however many passes it has
+    /// and however big the dial is. Any other repeat runs until its passes start going round a cycle, which is
+    /// known up front, and copies what that cycle did for the rest.
+    pub fn run(&self, dial: &mut Dial) -> Result<Tally, ProgramError> {
+        let mut tally = Tally::default();
+        run_block(&self.statements, dial, &mut tally, &mut None)?;
+        Ok(tally)
+    }
+    /// Same as `run`, but calls `observe` with every turn as it happens.
+    /// Repeats turn through their passes until the passes start to go round a cycle, so that every different pass
+    /// is observed. Turns in the cycles that are skipped after that never happen, so they aren't observed.
+    pub fn run_observed(
+        &self,
+        dial: &mut Dial,
+        mut observe: impl FnMut(Instruction, DialEvent),
+    ) -> Result<Tally, ProgramError> {
+        let mut tally = Tally::default();
+        run_block(&self.statements, dial, &mut tally, &mut Some(&mut observe))?;
+        Ok(tally)
+    }
+}
+impl FromStr for Program {
+    type Err = ProgramError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { input: s, pos: 0 };
+        let statements = parser.block(None)?;
+        Ok(Program { statements })
+    }
+}
+
+/// Called with every turn a program makes and what happened during it, if anything is watching.
+type Observer<'a> = Option<&'a mut dyn FnMut(Instruction, DialEvent)>;
+
+fn run_block(
+    statements: &[Statement],
+    dial: &mut Dial,
+    tally: &mut Tally,
+    observe: &mut Observer,
+) -> Result<(), ProgramError> {
+    for statement in statements {
+        match statement {
+            Statement::Turn { instruction, span } => {
+                turn(*instruction, span, dial, tally, observe)?;
+            }
+            Statement::Set { position, span } => {
+                let instruction = shortest_turn(dial, *position).ok_or_else(|| {
+                    ProgramError::new(
+                        span.clone(),
+                        ProgramErrorKind::NotOnDial(*position, dial.size()),
+                    )
+                })?;
+                turn(instruction, span, dial, tally, observe)?;
+            }
+            Statement::Repeat { count, body, span } => {
+                run_repeat(*count, body, span, dial, tally, observe)?
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Runs `body` `count` times, without turning through every pass.
+///
+/// The only state a pass carries over to the next is the dial's position, so:
+/// - A body of plain turns moves the dial the same distance round every pass, and is worked out in closed form
+///   by `skip_turns`. That takes O(turns * targets * log size) however many passes there are. When the turns are
+///   being observed, the passes up to the first cycle are turned through first so that each of them is seen.
+/// - A body with a `=` in it ends in the same place wherever it starts, so from the second pass on every pass
+///   starts from the same position and does the same thing. The second pass runs and the rest copy it.
+/// - Any other body has repeats of its own but still moves the dial the same distance `d` round every pass, so
+///   the passes go round a cycle of `size / gcd(d, size)` start positions. One cycle runs and the rest copy it.
+///   Its nested repeats are worked out the same way each time, but this can still run the body up to `size` times.
+fn run_repeat(
+    count: u64,
+    body: &[Statement],
+    span: &Range<usize>,
+    dial: &mut Dial,
+    tally: &mut Tally,
+    observe: &mut Observer,
+) -> Result<(), ProgramError> {
+    if count == 0 {
+        return Ok(());
+    }
+    let size = dial.size() as u64;
+    let Some(d) = displacement(body, size) else {
+        // Every pass after the first starts where the first one ended.
+        run_block(body, dial, tally, observe)?;
+        return run_cycles(count - 1, 1, body, span, dial, tally, observe);
+    };
+    let period = size / gcd(d, size);
+    match plain_turns(body) {
+        Some(turns) => {
+            let turned = match observe {
+                Some(_) => count.min(period),
+                None => 0,
+            };
+            for _ in 0..turned {
+                run_block(body, dial, tally, observe)?;
+            }
+            skip_turns(count - turned, &turns, span, dial, tally)
+        }
+        None => run_cycles(count, period, body, span, dial, tally, observe),
+    }
+}
+
+/// Runs `count` passes of `body`, which are known to come back round to the same start position every `period`
+/// passes. One cycle is run and then copied for the rest of the whole cycles.
+fn run_cycles(
+    count: u64,
+    period: u64,
+    body: &[Statement],
+    span: &Range<usize>,
+    dial: &mut Dial,
+    tally: &mut Tally,
+    observe: &mut Observer,
+) -> Result<(), ProgramError> {
+    let cycles = count / period;
+    if cycles > 0 {
+        let (tally_then, hits_then) = (*tally, dial.hits().to_vec());
+        for _ in 0..period {
+            run_block(body, dial, tally, observe)?;
+        }
+        explain::record(|| {
+            Event::new("dial_repeat_skip")
+                .with("period", period)
+                .with("cycles", cycles - 1)
+        });
+        let overflow = || ProgramError::new(span.clone(), ProgramErrorKind::Overflow);
+        *tally = tally
+            .extrapolated(&tally_then, cycles - 1)
+            .ok_or_else(overflow)?;
+        dial.extrapolate_hits(&hits_then, cycles - 1)
+            .ok_or_else(overflow)?;
+    }
+    // What's left is shorter than a cycle, so it just runs as normal.
+    for _ in 0..count % period {
+        run_block(body, dial, tally, observe)?;
+    }
+    Ok(())
+}
+
+/// The instructions of a block, if it is nothing but plain turns.
+fn plain_turns(body: &[Statement]) -> Option<Vec<Instruction>> {
+    body.iter()
+        .map(|statement| match statement {
+            Statement::Turn { instruction, .. } => Some(*instruction),
+            _ => None,
+        })
+        .collect()
+}
+
+/// How far clockwise one pass of `body` moves the dial, or `None` if a `=` in it runs.
+/// Either way it doesn't depend on where the pass starts.
+fn displacement(body: &[Statement], size: u64) -> Option<u64> {
+    let mut total = 0;
+    for statement in body {
+        let d = match statement {
+            Statement::Turn { instruction, .. } => clockwise(*instruction, size),
+            Statement::Set { .. } => return None,
+            // A `=` that never runs doesn't fix where the dial ends up.
+            Statement::Repeat { count: 0, .. } => 0,
+            Statement::Repeat { count, body, .. } => {
+                let d = displacement(body, size)?;
+                ((*count % size) as u128 * d as u128 % size as u128) as u64
+            }
+        };
+        total = (total + d) % size;
+    }
+    Some(total)
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Adds on what `count` passes of `turns` would do, without turning through them.
+///
+/// Each pass moves the dial `d` further round, so the `i`th pass makes the same turns as the first, just starting
+/// `i * d` further round. Whether a turn hits a target then comes down to a sum of `floor((a * i + b) / size)` over
+/// the passes, which `floor_sum` works out without going through them one by one.
+fn skip_turns(
+    count: u64,
+    turns: &[Instruction],
+    span: &Range<usize>,
+    dial: &mut Dial,
+    tally: &mut Tally,
+) -> Result<(), ProgramError> {
+    if count == 0 {
+        return Ok(());
+    }
+    explain::record(|| Event::new("dial_repeat_skip").with("passes", count));
+    let size = dial.size() as u64;
+    let start = dial.position() as u64;
+    let d = turns.iter().fold(0, |d, &instruction| {
+        (d + clockwise(instruction, size)) % size
+    });
+    let skip = || -> Option<(Tally, Vec<TargetHits>)> {
+        let mut skipped = Tally::default();
+        let mut added = dial
+            .hits()
+            .iter()
+            .map(|hits| TargetHits::new(hits.target))
+            .collect::<Vec<_>>();
+        // Where each turn starts on the first pass.
+        let mut from = start;
+        for &instruction in turns {
+            let to = (from + clockwise(instruction, size)) % size;
+            for hits in &mut added {
+                let target = hits.target as u64;
+                let crossings = crossings_over(count, size, d, from, target, instruction)?;
+                let landings = landings_over(count, size, d, to, target)?;
+                hits.crossings = hits.crossings.checked_add(crossings)?;
+                hits.landings = hits.landings.checked_add(landings)?;
+                skipped.crossings = skipped.crossings.checked_add(crossings)?;
+                skipped.landings = skipped.landings.checked_add(landings)?;
+            }
+            // Going clockwise the dial wraps when it moves onto 0, anticlockwise when it moves onto the top.
+            let wraps_at = match instruction.direction {
+                Direction::Clockwise => 0,
+                Direction::AntiClockwise => size - 1,
+            };
+            let wraps = crossings_over(count, size, d, from, wraps_at, instruction)?;
+            skipped.wraps = skipped.wraps.checked_add(wraps)?;
+            from = to;
+        }
+        Some((skipped, added))
+    };
+    let overflow = || ProgramError::new(span.clone(), ProgramErrorKind::Overflow);
+    let (skipped, added) = skip().ok_or_else(overflow)?;
+    let end = (start as u128 + count as u128 * d as u128) % size as u128;
+    *tally = tally.checked_add_tally(&skipped).ok_or_else(overflow)?;
+    dial.skip_to(end as u32, &added).ok_or_else(overflow)?;
+    Ok(())
+}
+
+/// How far clockwise a turn moves the dial.
+fn clockwise(instruction: Instruction, size: u64) -> u64 {
+    match instruction.direction {
+        Direction::Clockwise => instruction.steps % size,
+        Direction::AntiClockwise => (size - instruction.steps % size) % size,
+    }
+}
+
+/// The number of clicks that leave the dial pointing at `target` when `instruction` is turned `count` times,
+/// from `from`, then `from + d`, then `from + 2d` and so on.
+fn crossings_over(
+    count: u64,
+    size: u64,
+    d: u64,
+    from: u64,
+    target: u64,
+    instruction: Instruction,
+) -> Option<u128> {
+    // A turn hits the target `floor((steps + x) / size)` times, where `x` is how far the dial starts past the
+    // target in the direction it turns. Each pass `x` moves `step` further on.
+    let (x, step) = match instruction.direction {
+        Direction::Clockwise => ((from + size - target) % size, d),
+        Direction::AntiClockwise => ((target + size - from) % size, (size - d) % size),
+    };
+    // Splitting off the whole turns leaves `floor((partial + (x + i * step) % size) / size)` to sum up, and taking
+    // the remainder apart turns that into the difference of two floor sums.
+    let (turns, partial) = (instruction.steps / size, instruction.steps % size);
+    let (count, size, step) = (count as u128, size as u128, step as u128);
+    let (x, partial) = (x as u128, partial as u128);
+    let arcs = floor_sum(count, size, step, partial + x)? - floor_sum(count, size, step, x)?;
+    (count * turns as u128).checked_add(arcs)
+}
+
+/// The number of times a turn ending at `to`, then `to + d`, then `to + 2d` and so on for `count` passes, leaves the
+/// dial on `target`.
+fn landings_over(count: u64, size: u64, d: u64, to: u64, target: u64) -> Option<u128> {
+    // `y` is a multiple of `size` exactly when `floor((y + size) / size)` and `floor((y + size - 1) / size)` differ.
+    let y = ((to + size - target) % size) as u128;
+    let (count, size, d) = (count as u128, size as u128, d as u128);
+    Some(floor_sum(count, size, d, y + size)? - floor_sum(count, size, d, y + size - 1)?)
+}
+
+/// The sum of `floor((a * i + b) / m)` for `i` in `0..n`, or `None` if it doesn't fit.
+/// Works like Euclid's algorithm, swapping `a` and `m` round each time, so it takes O(log m) rounds.
+fn floor_sum(mut n: u128, mut m: u128, mut a: u128, mut b: u128) -> Option<u128> {
+    let mut sum = 0u128;
+    loop {
+        if a >= m {
+            let triangle = n * n.saturating_sub(1) / 2;
+            sum = sum.checked_add(triangle.checked_mul(a / m)?)?;
+            a %= m;
+        }
+        if b >= m {
+            sum = sum.checked_add(n.checked_mul(b / m)?)?;
+            b %= m;
+        }
+        let y_max = a.checked_mul(n)?.checked_add(b)?;
+        if y_max < m {
+            return Some(sum);
+        }
+        n = y_max / m;
+        b = y_max % m;
+        std::mem::swap(&mut m, &mut a);
+    }
+}
+
+/// Turns the dial and adds what happened to the tally.
+fn turn(
+    instruction: Instruction,
+    span: &Range<usize>,
+    dial: &mut Dial,
+    tally: &mut Tally,
+    observe: &mut Observer,
+) -> Result<(), ProgramError> {
+    let event = dial.step(instruction);
+    if let Some(observe) = observe {
+        observe(instruction, event);
+    }
+    *tally = tally
+        .checked_add(event)
+        .ok_or_else(|| ProgramError::new(span.clone(), ProgramErrorKind::Overflow))?;
+    Ok(())
+}
+
+/// The turn that takes the dial to `position` the shortest way round, or `None` if the position isn't on the dial.
+fn shortest_turn(dial: &Dial, position: u32) -> Option<Instruction> {
+    let size = dial.size();
+    if position >= size {
+        return None;
+    }
+    let (size, position, from) = (size as u64, position as u64, dial.position() as u64);
+    let clockwise = (position + size - from) % size;
+    let anticlockwise = (from + size - position) % size;
+    Some(if clockwise <= anticlockwise {
+        Instruction {
+            direction: Direction::Clockwise,
+            steps: clockwise,
+        }
+    } else {
+        Instruction {
+            direction: Direction::AntiClockwise,
+            steps: anticlockwise,
+        }
+    })
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    /// Byte offset of the next character to read.
+    pos: usize,
+}
+impl Parser<'_> {
+    /// Parses statements up to the end of the input, or up to the `)` closing the repeat opened at `opened_at`.
+    fn block(&mut self, opened_at: Option<usize>) -> Result<Vec<Statement>, ProgramError> {
+        let mut statements = vec![];
+        loop {
+            self.skip_whitespace_and_comments();
+            let start = self.pos;
+            let Some(c) = self.next_char() else {
+                return match opened_at {
+                    Some(open) => Err(self.error(open..open + 1, ProgramErrorKind::UnclosedRepeat)),
+                    None => Ok(statements),
+                };
+            };
+            let statement = match c {
+                ')' if opened_at.is_some() => return Ok(statements),
+                ')' => return Err(self.error(start..self.pos, ProgramErrorKind::UnmatchedClose)),
+                'L' | 'R' => {
+                    let direction = if c == 'L' {
+                        Direction::AntiClockwise
+                    } else {
+                        Direction::Clockwise
+                    };
+                    let steps = self.number()?;
+                    Statement::Turn {
+                        instruction: Instruction { direction, steps },
+                        span: start..self.pos,
+                    }
+                }
+                '=' => {
+                    let position = self.number()?;
+                    Statement::Set {
+                        position,
+                        span: start..self.pos,
+                    }
+                }
+                'x' => {
+                    let count = self.number()?;
+                    let span = start..self.pos;
+                    self.skip_whitespace_and_comments();
+                    let open = self.pos;
+                    if self.next_char() != Some('(') {
+                        return Err(self
+                            .error(open..self.pos.max(open + 1), ProgramErrorKind::ExpectedOpen));
+                    }
+                    let body = self.block(Some(open))?;
+                    Statement::Repeat { count, body, span }
+                }
+                c => return Err(self.error(start..self.pos, ProgramErrorKind::UnexpectedChar(c))),
+            };
+            statements.push(statement);
+        }
+    }
+    fn number<T: FromStr>(&mut self) -> Result<T, ProgramError> {
+        let start = self.pos;
+        let digits = self.input[start..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.input.len() - start);
+        self.pos += digits;
+        if digits == 0 {
+            return Err(self.error(start..start + 1, ProgramErrorKind::ExpectedNumber));
+        }
+        self.input[start..self.pos]
+            .parse()
+            // It's all digits, so the only way it can fail is by not fitting.
+            .map_err(|_| self.error(start..self.pos, ProgramErrorKind::NumberTooLarge))
+    }
+    fn skip_whitespace_and_comments(&mut self) {
+        loop {
+            let rest = &self.input[self.pos..];
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if !trimmed.starts_with('#') {
+                return;
+            }
+            self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+        }
+    }
+    fn next_char(&mut self) -> Option<char> {
+        let c = self.input[self.pos..].chars().next()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+    fn error(&self, span: Range<usize>, kind: ProgramErrorKind) -> ProgramError {
+        let span = span.start.min(self.input.len())..span.end.min(self.input.len());
+        let before = &self.input[..span.start];
+        let line = before.matches('\n').count() + 1;
+        let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+        ProgramError {
+            span,
+            line: Some((line, column)),
+            kind,
+        }
+    }
+}
+
+/// Something wrong with a program, along with where in the input it is.
+#[derive(Debug, Error)]
+pub struct ProgramError {
+    /// The byte range of the input that is wrong.
+    pub span: Range<usize>,
+    /// The line and column the span starts on, both counting from 1.
+    pub line: Option<(usize, usize)>,
+    pub kind: ProgramErrorKind,
+}
+impl ProgramError {
+    fn new(span: Range<usize>, kind: ProgramErrorKind) -> Self {
+        ProgramError {
+            span,
+            line: None,
+            kind,
+        }
+    }
+}
+impl Display for ProgramError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some((line, column)) => write!(f, "{} at line {line}, column {column}", self.kind),
+            None => write!(
+                f,
+                "{} at bytes {}..{}",
+                self.kind, self.span.start, self.span.end
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ProgramErrorKind {
+    #[error("Unexpected character {0:?}, expected 'L', 'R', '=', 'x' or '#'")]
+    UnexpectedChar(char),
+    #[error("Expected a number")]
+    ExpectedNumber,
+    #[error("Number is too large")]
+    NumberTooLarge,
+    #[error("Expected '(' to start the repeated block")]
+    ExpectedOpen,
+    #[error("Repeated block is never closed")]
+    UnclosedRepeat,
+    #[error("')' without a repeated block to close")]
+    UnmatchedClose,
+    #[error("Position {0} is not on a dial with {1} positions")]
+    NotOnDial(u32, u32),
+    #[error("The totals are too large to count")]
+    Overflow,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DialConfig;
+
+    fn run(program: &str, config: &DialConfig) -> (Tally, Dial) {
+        let mut dial = Dial::new(config).unwrap();
+        let tally = program.parse::<Program>().unwrap().run(&mut dial).unwrap();
+        (tally, dial)
+    }
+
+    /// Runs a program the slow way, by writing out every repeat.
+    fn unrolled(statements: &[Statement]) -> String {
+        statements
+            .iter()
+            .map(|statement| match statement {
+                Statement::Turn { instruction, .. } => {
+                    format!("{}{}", instruction.direction, instruction.steps)
+                }
+                Statement::Set { position, .. } => format!("={position}"),
+                Statement::Repeat { count, body, .. } => {
+                    vec![unrolled(body); *count as usize].join(" ")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_plain_instructions_match_part_answers() {
+        let (tally, _) = run(include_str!("../inputs/test.txt"), &DialConfig::default());
+        assert_eq!((tally.landings, tally.crossings), (3, 6));
+    }
+
+    #[test]
+    fn test_parse_nested_repeats_and_comments() {
+        let program = "# Warm up\nR10 x2(L5 # back a bit\n  x3(=0))"
+            .parse::<Program>()
+            .unwrap();
+        let expected = vec![
+            Statement::Turn {
+                instruction: Instruction {
+                    direction: Direction::Clockwise,
+                    steps: 10,
+                },
+                span: 10..13,
+            },
+            Statement::Repeat {
+                count: 2,
+                body: vec![
+                    Statement::Turn {
+                        instruction: Instruction {
+                            direction: Direction::AntiClockwise,
+                            steps: 5,
+                        },
+                        span: 17..19,
+                    },
+                    Statement::Repeat {
+                        count: 3,
+                        body: vec![Statement::Set {
+                            position: 0,
+                            span: 38..40,
+                        }],
+                        span: 35..37,
+                    },
+                ],
+                span: 14..16,
+            },
+        ];
+        assert_eq!(program.statements, expected);
+    }
+
+    #[test]
+    fn test_set_takes_shortest_way() {
+        let config = DialConfig {
+            size: 100,
+            start: 10,
+            targets: vec![0, 50],
+        };
+        // 90 is 20 clicks anticlockwise, passing 0 on the way.
+        let (tally, dial) = run("=90", &config);
+        assert_eq!(dial.position(), 90);
+        assert_eq!((tally.landings, tally.crossings), (0, 1));
+        // 50 away either way, so it goes clockwise, passing 50 but not 0.
+        let (tally, dial) = run("=60", &config);
+        assert_eq!(dial.position(), 60);
+        assert_eq!((tally.landings, tally.crossings), (0, 1));
+    }
+
+    #[test]
+    fn test_repeats_match_unrolled() {
+        let check = |program: &str, config: &DialConfig| {
+            let parsed = program.parse::<Program>().unwrap();
+            let (tally, dial) = run(program, config);
+            let (expected_tally, expected_dial) = run(&unrolled(&parsed.statements), config);
+            assert_eq!(tally, expected_tally, "{program} on {}", config.size);
+            assert_eq!(dial, expected_dial, "{program} on {}", config.size);
+        };
+        let config = DialConfig {
+            size: 60,
+            start: 7,
+            targets: vec![0, 15, 45],
+        };
+        for program in [
+            "x100(R7)",
+            "x37(L13 R2)",
+            "R5 x9(x4(L11) =30 R50)",
+            "x50(R60) x3(=45)",
+            "x0(R1) L1",
+        ] {
+            check(program, &config);
+        }
+        // Every kind of repeat, on a dial small enough for the passes to wrap round it many times.
+        let small = DialConfig {
+            size: 7,
+            start: 3,
+            targets: vec![6, 0],
+        };
+        for program in [
+            "x100(R7)",
+            "x37(L13 R2)",
+            "x13(L61 R1000 L7)",
+            "x60(R15)",
+            "R5 x9(x4(L11) =3 R50)",
+            "x25(R3 x4(L11) R100)",
+            "x7(x3(R13 L4))",
+            "x4(x0(=2) L9)",
+        ] {
+            check(program, &config);
+            check(program, &small);
+        }
+    }
+
+    #[test]
+    fn test_floor_sum() {
+        for (n, m, a, b) in [(0, 5, 3, 2), (10, 7, 3, 5), (25, 4, 9, 14), (13, 13, 13, 0)] {
+            let expected = (0..n).map(|i| (a * i + b) / m).sum::<u128>();
+            assert_eq!(floor_sum(n, m, a, b), Some(expected), "{n} {m} {a} {b}");
+        }
+    }
+
+    #[test]
+    fn test_huge_repeat() {
+        // Far too many to unroll. Every 100 repeats go round the dial 7 times, landing on 0 once.
+        let (tally, dial) = run("x1000000000000(R7)", &DialConfig::default());
+        assert_eq!(dial.position(), 50);
+        assert_eq!(tally.landings, 10_000_000_000);
+        assert_eq!(tally.crossings, 70_000_000_000);
+    }
+
+    #[test]
+    fn test_huge_repeat_on_huge_dial() {
+        let config = DialConfig {
+            size: 4_000_000_000,
+            start: 50,
+            targets: vec![0],
+        };
+        // 7 * 10^12 clicks go round 1750 times and end back at 50. 7 is coprime to the size, so the passes land
+        // on 0 once every 4 * 10^9 of them.
+        let (tally, dial) = run("x1000000000000(R7)", &config);
+        assert_eq!(dial.position(), 50);
+        assert_eq!(
+            (tally.landings, tally.crossings, tally.wraps),
+            (250, 1750, 1750)
+        );
+    }
+
+    #[test]
+    fn test_error_spans() {
+        let error = "R10\nL5 Q3".parse::<Program>().unwrap_err();
+        assert!(matches!(error.kind, ProgramErrorKind::UnexpectedChar('Q')));
+        assert_eq!((error.span, error.line), (7..8, Some((2, 4))));
+
+        let error = "x3(L5".parse::<Program>().unwrap_err();
+        assert!(matches!(error.kind, ProgramErrorKind::UnclosedRepeat));
+        assert_eq!(error.span, 2..3);
+
+        let error = "L5)".parse::<Program>().unwrap_err();
+        assert!(matches!(error.kind, ProgramErrorKind::UnmatchedClose));
+
+        let error = "R99999999999999999999".parse::<Program>().unwrap_err();
+        assert!(matches!(error.kind, ProgramErrorKind::NumberTooLarge));
+        assert_eq!(error.span, 1..21);
+
+        let error = "L".parse::<Program>().unwrap_err();
+        assert!(matches!(error.kind, ProgramErrorKind::ExpectedNumber));
+
+        let mut dial = Dial::new(&DialConfig::default()).unwrap();
+        let error = "R1 =100"
+            .parse::<Program>()
+            .unwrap()
+            .run(&mut dial)
+            .unwrap_err();
+        assert!(matches!(error.kind, ProgramErrorKind::NotOnDial(100, 100)));
+        assert_eq!(error.span, 3..7);
+    }
+
+    #[test]
+    fn test_overflowing_totals() {
+        let config = DialConfig {
+            size: 1,
+            start: 0,
+            targets: vec![0],
+        };
+        let max = u64::MAX;
+        for (program, span) in [
+            (format!("x{max}(x{max}(R{max}))"), 0..21),
+            // The repeat and two more turns fit exactly, so it's the third turn that goes over.
+            (format!("x{max}(R{max}) R{max} R{max} R{max}"), 89..110),
+        ] {
+            let mut dial = Dial::new(&config).unwrap();
+            let error = program
+                .parse::<Program>()
+                .unwrap()
+                .run(&mut dial)
+                .unwrap_err();
+            assert!(
+                matches!(error.kind, ProgramErrorKind::Overflow),
+                "{program}"
+            );
+            assert_eq!(error.span, span, "{program}");
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::io::BufRead;
+
+use thiserror::Error;
+
+use crate::{Dial, Instruction, ParseError, Tally};
+
+/// Works through instructions as they are read, one line at a time, so that memory use stays the same
+/// however long the input is. Meant for piping in a live log of instructions.
+#[derive(Debug, Clone)]
+pub struct DialRunner {
+    dial: Dial,
+    tally: Tally,
+    /// The number of instructions applied so far.
+    instructions: u64,
+    /// How many instructions to apply between progress reports, never if 0.
+    report_every: u64,
+}
+
+/// The running totals, as passed to the progress callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub instructions: u64,
+    pub position: u32,
+    pub tally: Tally,
+}
+
+#[derive(Debug, Error)]
+pub enum RunnerError {
+    #[error("Failed to read instructions")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid instruction on line {line}")]
+    Parse {
+        line: u64,
+        #[source]
+        source: ParseError,
+    },
+}
+
+impl DialRunner {
+    pub fn new(dial: Dial) -> Self {
+        DialRunner {
+            dial,
+            tally: Tally::default(),
+            instructions: 0,
+            report_every: 0,
+        }
+    }
+    /// Reports progress after every `n` instructions. 0 turns reporting off.
+    pub fn report_every(mut self, n: u64) -> Self {
+        self.report_every = n;
+        self
+    }
+    pub fn progress(&self) -> Progress {
+        Progress {
+            instructions: self.instructions,
+            position: self.dial.position(),
+            tally: self.tally,
+        }
+    }
+    /// Applies a single instruction, reporting progress if it's time to.
+    pub fn step(&mut self, instruction: Instruction, report: &mut impl FnMut(&Progress)) {
+        self.tally.add(self.dial.step(instruction));
+        self.instructions += 1;
+        if self.report_every != 0 && self.instructions.is_multiple_of(self.report_every) {
+            report(&self.progress());
+        }
+    }
+    /// Reads instructions until the reader runs dry, one per line with blank lines skipped.
+    /// Returns the totals over everything this runner has seen, including any earlier calls.
+    pub fn run<R: BufRead>(
+        &mut self,
+        mut reader: R,
+        mut report: impl FnMut(&Progress),
+    ) -> Result<Tally, RunnerError> {
+        // The one buffer is reused for every line, so a long input doesn't mean more memory.
+        let mut line = String::new();
+        let mut line_number = 0;
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                return Ok(self.tally);
+            }
+            line_number += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let instruction = line.parse().map_err(|source| RunnerError::Parse {
+                line: line_number,
+                source,
+            })?;
+            self.step(instruction, &mut report);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DialConfig;
+
+    fn runner() -> DialRunner {
+        DialRunner::new(Dial::new(&DialConfig::default()).unwrap())
+    }
+
+    #[test]
+    fn test_run_example() {
+        let input = include_str!("../inputs/test.txt");
+        let tally = runner().run(input.as_bytes(), |_| {}).unwrap();
+        assert_eq!((tally.landings, tally.crossings), (3, 6));
+    }
+
+    #[test]
+    fn test_periodic_reports() {
+        let input = include_str!("../inputs/test.txt");
+        let mut reports = vec![];
+        runner()
+            .report_every(4)
+            .run(input.as_bytes(), |progress| reports.push(*progress))
+            .unwrap();
+        let summary = reports
+            .iter()
+            .map(|p| {
+                (
+                    p.instructions,
+                    p.position,
+                    p.tally.landings,
+                    p.tally.crossings,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(summary, vec![(4, 95, 1, 2), (8, 0, 3, 5)]);
+    }
+
+    #[test]
+    fn test_parse_error_line() {
+        let input = "L68\r\n\r\nR1O\r\n";
+        let error = runner().run(input.as_bytes(), |_| {}).unwrap_err();
+        assert!(matches!(error, RunnerError::Parse { line: 3, .. }));
+    }
+
+    #[test]
+    fn test_totals_carry_over_between_runs() {
+        let mut runner = runner();
+        runner.run("L50\n".as_bytes(), |_| {}).unwrap();
+        let tally = runner.run("R100\n".as_bytes(), |_| {}).unwrap();
+        assert_eq!((tally.landings, tally.crossings), (2, 2));
+        assert_eq!(runner.progress().instructions, 2);
+    }
+}
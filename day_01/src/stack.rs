@@ -0,0 +1,118 @@
+use crate::{Dial, DialConfig, DialError, Instruction};
+
+/// Several dials coupled together like an odometer or the wheel pack of a safe.
+///
+/// Instructions turn the first wheel. Every time a wheel wraps round past the end of its range it drives the
+/// next wheel along by one click in the same direction, so the last wheel turns the slowest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialStack {
+    /// The wheels, from the one that is turned by hand to the one that is driven last.
+    dials: Vec<Dial>,
+}
+impl DialStack {
+    /// Creates a stack with one wheel per config, the first config being the wheel that is turned by hand.
+    pub fn new(configs: &[DialConfig]) -> Result<Self, DialError> {
+        if configs.is_empty() {
+            return Err(DialError::EmptyStack);
+        }
+        let dials = configs.iter().map(Dial::new).collect::<Result<_, _>>()?;
+        Ok(DialStack { dials })
+    }
+    /// The wheels in the order they are driven. Each one knows its position and how often it hit its targets.
+    pub fn dials(&self) -> &[Dial] {
+        &self.dials
+    }
+    /// The position of every wheel.
+    pub fn positions(&self) -> Vec<u32> {
+        self.dials.iter().map(Dial::position).collect()
+    }
+    /// Turns the first wheel according to the instruction, carrying over into the rest of the stack.
+    pub fn step(&mut self, instruction: Instruction) {
+        let mut instruction = instruction;
+        for dial in &mut self.dials {
+            let event = dial.step(instruction);
+            // A wheel that doesn't wrap leaves every wheel after it where it is.
+            if event.wraps == 0 {
+                break;
+            }
+            instruction = Instruction {
+                direction: instruction.direction,
+                steps: event.wraps,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn odometer(wheels: usize) -> DialStack {
+        let config = DialConfig {
+            size: 10,
+            start: 0,
+            targets: vec![0],
+        };
+        DialStack::new(&vec![config; wheels]).unwrap()
+    }
+
+    #[test]
+    fn test_odometer_counts_up() {
+        let mut stack = odometer(3);
+        stack.step("R7".parse().unwrap());
+        assert_eq!(stack.positions(), vec![7, 0, 0]);
+        stack.step("R128".parse().unwrap());
+        // 7 + 128 = 135, read from the slowest wheel down.
+        assert_eq!(stack.positions(), vec![5, 3, 1]);
+    }
+
+    #[test]
+    fn test_odometer_counts_down() {
+        let mut stack = odometer(3);
+        stack.step("R135".parse().unwrap());
+        stack.step("L36".parse().unwrap());
+        assert_eq!(stack.positions(), vec![9, 9, 0]);
+        // Going below zero rolls every wheel over.
+        stack.step("L100".parse().unwrap());
+        assert_eq!(stack.positions(), vec![9, 9, 9]);
+    }
+
+    #[test]
+    fn test_target_hits_per_wheel() {
+        let mut stack = odometer(2);
+        stack.step("R25".parse().unwrap());
+        let hits = stack
+            .dials()
+            .iter()
+            .map(|dial| (dial.hits()[0].landings, dial.hits()[0].crossings))
+            .collect::<Vec<_>>();
+        // The first wheel passes 0 twice, which drives the second wheel on to 2 without passing 0.
+        assert_eq!(hits, vec![(0, 2), (0, 0)]);
+    }
+
+    #[test]
+    fn test_mixed_wheel_sizes() {
+        let configs = [
+            DialConfig {
+                size: 60,
+                start: 50,
+                targets: vec![0],
+            },
+            DialConfig {
+                size: 24,
+                start: 23,
+                targets: vec![0],
+            },
+        ];
+        let mut stack = DialStack::new(&configs).unwrap();
+        // Like a clock: 10 minutes past 23:50 is midnight.
+        stack.step("R10".parse().unwrap());
+        assert_eq!(stack.positions(), vec![0, 0]);
+        assert_eq!(stack.dials()[1].hits()[0].landings, 1);
+    }
+
+    #[test]
+    fn test_empty_stack() {
+        assert!(matches!(DialStack::new(&[]), Err(DialError::EmptyStack)));
+    }
+}
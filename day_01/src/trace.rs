@@ -0,0 +1,239 @@
+use std::{io::Write, path::Path};
+
+use crate::{Dial, Instruction, Program, ProgramError};
+
+/// One row of a dial's journey, one per instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceRow {
+    pub index: usize,
+    pub instruction: Instruction,
+    pub from: u32,
+    pub to: u32,
+    /// The number of clicks that left the dial pointing at a target.
+    pub crossings: u128,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceFormat {
+    Csv,
+    Json,
+}
+impl TraceFormat {
+    /// JSON for `.json` files, CSV for anything else.
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => TraceFormat::Json,
+            _ => TraceFormat::Csv,
+        }
+    }
+}
+
+/// Runs the program on the dial, recording a row for each turn it makes.
+/// Turns in repeat cycles that the program skips over don't get a row.
+pub fn trace(dial: &mut Dial, program: &Program) -> Result<Vec<TraceRow>, ProgramError> {
+    let mut rows = vec![];
+    program.run_observed(dial, |instruction, event| {
+        rows.push(TraceRow {
+            index: rows.len(),
+            instruction,
+            from: event.from,
+            to: event.position,
+            crossings: event.crossings,
+        })
+    })?;
+    Ok(rows)
+}
+
+/// Writes the trace as CSV with a header row, or as a JSON array of objects.
+pub fn write_trace(
+    rows: &[TraceRow],
+    format: TraceFormat,
+    mut w: impl Write,
+) -> std::io::Result<()> {
+    match format {
+        TraceFormat::Csv => {
+            writeln!(w, "index,instruction,from,to,crossings")?;
+            for row in rows {
+                writeln!(
+                    w,
+                    "{},{}{},{},{},{}",
+                    row.index,
+                    row.instruction.direction,
+                    row.instruction.steps,
+                    row.from,
+                    row.to,
+                    row.crossings
+                )?;
+            }
+        }
+        TraceFormat::Json => {
+            writeln!(w, "[")?;
+            for (i, row) in rows.iter().enumerate() {
+                let comma = if i + 1 < rows.len() { "," } else { "" };
+                writeln!(
+                    w,
+                    r#"  {{"index":{},"instruction":"{}{}","from":{},"to":{},"crossings":{}}}{comma}"#,
+                    row.index,
+                    row.instruction.direction,
+                    row.instruction.steps,
+                    row.from,
+                    row.to,
+                    row.crossings
+                )?;
+            }
+            writeln!(w, "]")?;
+        }
+    }
+    w.flush()
+}
+
+/// The most positions `render_ring` draws. Bigger dials only show this many around the pointer.
+const RING_WINDOW: u32 = 360;
+
+/// Draws the dial as a ring of `.` with the targets marked `o` and the pointer marked `@`.
+/// Position 0 is at the top and positions go round clockwise, with the current position written in the middle.
+/// Dials with more than `RING_WINDOW` positions only show that many positions centred on the pointer instead, with
+/// the pointer at the top.
+/// Characters are about twice as tall as they are wide, so the ring is drawn twice as wide as it is tall.
+pub fn render_ring(dial: &Dial, radius: usize) -> String {
+    let radius = radius.max(1);
+    let (height, width) = (2 * radius + 1, 4 * radius + 1);
+    let mut grid = vec![vec![' '; width]; height];
+    let size = dial.size() as u64;
+    // The position at the top of the ring, and how many positions go round it.
+    let (top, shown) = if size <= RING_WINDOW as u64 {
+        (0, size)
+    } else {
+        (dial.position() as u64, RING_WINDOW as u64)
+    };
+    // How far round the ring a position is, or `None` if it's outside the window.
+    let offset = |position: u32| {
+        let offset = (position as u64 + size - top + shown / 2) % size;
+        (offset < shown).then(|| (offset + shown - shown / 2) % shown)
+    };
+    let cell = |offset: u64| {
+        let angle = std::f64::consts::TAU * offset as f64 / shown as f64;
+        let x = radius as f64 * 2.0 * (1.0 + angle.sin());
+        let y = radius as f64 * (1.0 - angle.cos());
+        (y.round() as usize, x.round() as usize)
+    };
+    // Later marks win when several positions share a cell, so draw in order of importance.
+    for offset in 0..shown {
+        let (y, x) = cell(offset);
+        grid[y][x] = '.';
+    }
+    for hits in dial.hits() {
+        if let Some(offset) = offset(hits.target) {
+            let (y, x) = cell(offset);
+            grid[y][x] = 'o';
+        }
+    }
+    let (y, x) = cell(offset(dial.position()).unwrap_or(0));
+    grid[y][x] = '@';
+
+    let label = dial.position().to_string();
+    let start = (width / 2).saturating_sub(label.len() / 2);
+    for (i, c) in label.chars().enumerate() {
+        if let Some(slot) = grid[radius].get_mut(start + i) {
+            *slot = c;
+        }
+    }
+    grid.into_iter()
+        .map(|row| row.into_iter().collect::<String>().trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DialConfig;
+
+    fn example_trace() -> Vec<TraceRow> {
+        let program = "L68\nL30\nR48".parse::<Program>().unwrap();
+        let mut dial = Dial::new(&DialConfig::default()).unwrap();
+        trace(&mut dial, &program).unwrap()
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut out = vec![];
+        write_trace(&example_trace(), TraceFormat::Csv, &mut out).unwrap();
+        let expected =
+            "index,instruction,from,to,crossings\n0,L68,50,82,1\n1,L30,82,52,0\n2,R48,52,0,1\n";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_json() {
+        let mut out = vec![];
+        write_trace(&example_trace(), TraceFormat::Json, &mut out).unwrap();
+        let expected = r#"[
+  {"index":0,"instruction":"L68","from":50,"to":82,"crossings":1},
+  {"index":1,"instruction":"L30","from":82,"to":52,"crossings":0},
+  {"index":2,"instruction":"R48","from":52,"to":0,"crossings":1}
+]
+"#;
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_for_path() {
+        assert_eq!(
+            TraceFormat::for_path(Path::new("trace.JSON")),
+            TraceFormat::Json
+        );
+        assert_eq!(
+            TraceFormat::for_path(Path::new("trace.csv")),
+            TraceFormat::Csv
+        );
+        assert_eq!(TraceFormat::for_path(Path::new("trace")), TraceFormat::Csv);
+    }
+
+    #[test]
+    fn test_render_ring() {
+        let config = DialConfig {
+            size: 8,
+            start: 2,
+            targets: vec![0],
+        };
+        let dial = Dial::new(&config).unwrap();
+        let expected = ["    o", " .     .", ".   2   @", " .     .", "    ."].join("\n");
+        assert_eq!(render_ring(&dial, 2), expected);
+    }
+
+    #[test]
+    fn test_trace_program() {
+        let program = "=0 x2(R25) # comment".parse::<Program>().unwrap();
+        let mut dial = Dial::new(&DialConfig::default()).unwrap();
+        let rows = trace(&mut dial, &program).unwrap();
+        let rows = rows
+            .iter()
+            .map(|row| (row.index, row.from, row.to))
+            .collect::<Vec<_>>();
+        assert_eq!(rows, vec![(0, 50, 0), (1, 0, 25), (2, 25, 50)]);
+    }
+
+    #[test]
+    fn test_render_ring_on_huge_dial() {
+        let config = DialConfig {
+            size: 4_000_000_000,
+            start: 3_999_999_999,
+            targets: vec![89, 2_000_000_000],
+        };
+        let dial = Dial::new(&config).unwrap();
+        // The pointer is at the top and the target 90 clicks clockwise is a quarter of the way round the window.
+        // The target halfway round the dial is nowhere near the window so isn't drawn.
+        let expected = [
+            "   ...@...",
+            " ...     ...",
+            "..         ..",
+            ".3999999999 o",
+            "..         ..",
+            " ...     ...",
+            "   .......",
+        ]
+        .join("\n");
+        assert_eq!(render_ring(&dial, 3), expected);
+    }
+}
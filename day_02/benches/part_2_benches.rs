@@ -1,7 +1,8 @@
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr};
 
 use criterion::{Criterion, criterion_group, criterion_main};
 use solution::part_2::*;
+use solution::schedule::Scheduler;
 use solution::*;
 
 // Per the test data.
@@ -21,45 +22,86 @@ fn setup_test_database() -> ShopDatabase {
     ])
 }
 
-fn load_file() -> ShopDatabase {
-    let raw = std::fs::read_to_string("D:/code/AoC-2025/day_02/inputs/actual.txt").unwrap();
-    ShopDatabase::from_str(&raw).unwrap()
+/// The real input isn't checked in, so its benches are skipped when it isn't there.
+fn load_actual_database() -> Option<ShopDatabase> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("inputs/actual.txt");
+    match std::fs::read_to_string(&path) {
+        Ok(raw) => Some(ShopDatabase::from_str(&raw).unwrap()),
+        Err(_) => {
+            eprintln!(
+                "Skipping the actual data benches, {} not found",
+                path.display()
+            );
+            None
+        }
+    }
 }
 
-fn benchmark_invalid_multi_test(c: &mut Criterion) {
-    let db = setup_test_database();
-    c.bench_function("multi-threaded | test data", |b| {
-        b.iter(|| sum_all_invalid_in_db_multi(&db))
-    });
+/// About `ids` IDs spread over 20 ranges of 10 digit numbers, like the real input.
+/// The same seed always gives the same ranges, so runs can be compared.
+fn setup_synthetic_database(ids: u64, seed: u64) -> ShopDatabase {
+    let mut state = seed;
+    // SplitMix64, which is plenty random enough for picking ranges.
+    let mut next = move || {
+        state = state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    };
+    let per_range = (ids / 20).max(1);
+    let ranges = (0..20)
+        .map(|_| {
+            let start = 1_000_000_000 + next() % 8_000_000_000;
+            // Between half and one and a half times the average, so the ranges aren't all the same size.
+            let len = per_range / 2 + next() % per_range + 1;
+            Range::new(start, start + len - 1)
+        })
+        .collect::<Vec<_>>();
+    ShopDatabase::new(&ranges)
 }
 
-fn benchmark_invalid_multi_actual(c: &mut Criterion) {
-    // let db = setup_test_database();
-    let db = load_file();
-    c.bench_function("multi-threaded | actual data", |b| {
-        b.iter(|| sum_all_invalid_in_db_multi(&db))
-    });
+fn inputs() -> Vec<(String, ShopDatabase)> {
+    let mut inputs = vec![("test data".to_string(), setup_test_database())];
+    inputs.extend(load_actual_database().map(|db| ("actual data".to_string(), db)));
+    for ids in [1_000, 10_000, 50_000, 100_000, 1_000_000] {
+        inputs.push((
+            format!("synthetic {ids}"),
+            setup_synthetic_database(ids, 2025),
+        ));
+    }
+    inputs
 }
 
-fn benchmark_invalid_single_test(c: &mut Criterion) {
-    let db = setup_test_database();
-    c.bench_function("single-threaded | test data", |b| {
-        b.iter(|| sum_all_invalid_in_db_single(&db))
-    });
+// Every strategy on every input. The scheduler ones show where threads start paying for themselves,
+// and so where `DEFAULT_PARALLEL_THRESHOLD` should sit.
+fn benchmark_strategies(c: &mut Criterion) {
+    let serial = Scheduler::new().parallel_threshold(u64::MAX);
+    let parallel = Scheduler::new().parallel_threshold(0);
+    let adaptive = Scheduler::new();
+    for (name, db) in inputs() {
+        let mut group = c.benchmark_group(name);
+        group.bench_function("single-threaded", |b| {
+            b.iter(|| sum_all_invalid_in_db_single(&db))
+        });
+        group.bench_function("multi-threaded", |b| {
+            b.iter(|| sum_all_invalid_in_db_multi(&db))
+        });
+        group.bench_function("scheduled serial", |b| {
+            b.iter(|| sum_all_invalid_in_db_scheduled(&db, &serial))
+        });
+        group.bench_function("scheduled parallel", |b| {
+            b.iter(|| sum_all_invalid_in_db_scheduled(&db, &parallel))
+        });
+        group.bench_function("scheduled adaptive", |b| {
+            b.iter(|| sum_all_invalid_in_db_scheduled(&db, &adaptive))
+        });
+        group.bench_function("closed form", |b| {
+            b.iter(|| sum_all_invalid_in_db_closed_form(&db))
+        });
+        group.finish();
+    }
 }
 
-fn benchmark_invalid_single_actual(c: &mut Criterion) {
-    let db = load_file();
-    c.bench_function("single-threaded | actual data", |b| {
-        b.iter(|| sum_all_invalid_in_db_single(&db))
-    });
-}
-
-criterion_group!(
-    benches,
-    benchmark_invalid_multi_actual,
-    benchmark_invalid_single_actual,
-    benchmark_invalid_multi_test,
-    benchmark_invalid_single_test
-);
+criterion_group!(benches, benchmark_strategies);
 criterion_main!(benches);
@@ -1,21 +1,155 @@
 use anyhow::{Context, Result};
-use std::str::FromStr;
+pub use common::input::Normalization;
+pub use common::params::Params;
+use common::params::{Param, ParamSpec};
+use std::{
+    fmt::{Debug, Display},
+    num::{IntErrorKind, ParseIntError},
+    str::FromStr,
+};
 use thiserror::Error;
 
 pub mod part_1;
 pub mod part_2;
+pub mod repeats;
+pub mod schedule;
 
-pub fn run_part_1(input: &str) -> Result<u64> {
-    part_1::run(input).context("Failed to run part 1")
+/// How the input is cleaned up before it is parsed.
+pub const NORMALIZATION: Normalization = Normalization::Full;
+
+/// The base that the IDs are written in, both in the input and when looking for repeated blocks.
+pub const RADIX: Param<u32> = Param::new("radix", 10, "Base the IDs are written in, from 2 to 36");
+/// What to do about ranges that share IDs.
+pub const OVERLAPS: Param<OverlapPolicy> = Param::new(
+    "overlaps",
+    OverlapPolicy::Merge,
+    "merge to count shared IDs once, or warn to count them per range and log each overlap",
+);
+/// Whether the invalid IDs are summed in closed form or checked one by one on the scheduler.
+pub const METHOD: Param<Method> = Param::new(
+    "method",
+    Method::ClosedForm,
+    "closed-form to build the invalid IDs directly, or scheduled to check every ID, on threads once there are enough",
+);
+/// The threads the scheduled method can use.
+pub const THREADS: Param<usize> = Param::new(
+    "threads",
+    0,
+    "Threads for the scheduled method, 0 for one per core",
+);
+pub const PARAMS: &[&dyn ParamSpec] = &[&RADIX, &OVERLAPS, &METHOD, &THREADS];
+
+pub fn run_part_1(input: &str, params: &Params) -> Result<u128> {
+    let (radix, overlaps) = (params.get(&RADIX), params.get(&OVERLAPS));
+    match params.get(&METHOD) {
+        Method::ClosedForm => part_1::run(input, radix, overlaps),
+        Method::Scheduled => part_1::run_scheduled(input, radix, overlaps, &scheduler(params)?),
+    }
+    .context("Failed to run part 1")
+}
+
+pub fn run_part_2(input: &str, params: &Params) -> Result<u128> {
+    let (radix, overlaps) = (params.get(&RADIX), params.get(&OVERLAPS));
+    match params.get(&METHOD) {
+        Method::ClosedForm => part_2::run(input, radix, overlaps),
+        Method::Scheduled => part_2::run_scheduled(input, radix, overlaps, &scheduler(params)?),
+    }
+    .context("Failed to run part 2")
+}
+
+/// Runs part 1 straight from the lines, for inputs too big to load all at once.
+/// Overlaps and the method are dealt with the same way as `run_part_1`.
+pub fn stream_part_1<'a>(lines: impl Iterator<Item = &'a str>, params: &Params) -> Result<u128> {
+    let (radix, overlaps) = (params.get(&RADIX), params.get(&OVERLAPS));
+    match params.get(&METHOD) {
+        Method::ClosedForm => part_1::run_streaming(lines, radix, overlaps),
+        Method::Scheduled => {
+            part_1::run_streaming_scheduled(lines, radix, overlaps, &scheduler(params)?)
+        }
+    }
+    .context("Failed to run part 1")
+}
+
+/// Runs part 2 straight from the lines, for inputs too big to load all at once.
+/// Overlaps and the method are dealt with the same way as `run_part_2`.
+pub fn stream_part_2<'a>(lines: impl Iterator<Item = &'a str>, params: &Params) -> Result<u128> {
+    let (radix, overlaps) = (params.get(&RADIX), params.get(&OVERLAPS));
+    match params.get(&METHOD) {
+        Method::ClosedForm => part_2::run_streaming(lines, radix, overlaps),
+        Method::Scheduled => {
+            part_2::run_streaming_scheduled(lines, radix, overlaps, &scheduler(params)?)
+        }
+    }
+    .context("Failed to run part 2")
+}
+
+/// The scheduler for the scheduled method, running on as many threads as the params ask for.
+fn scheduler(params: &Params) -> Result<schedule::Scheduler> {
+    schedule::Scheduler::new()
+        .with_threads(params.get(&THREADS))
+        .context("Failed to start the scheduler's threads")
 }
 
-pub fn run_part_2(input: &str) -> Result<u64> {
-    part_2::run(input).context("Failed to run part 2")
+/// Parses ranges one at a time from the lines of an input, rather than collecting them into a `ShopDatabase`.
+/// The ranges can be split over any number of lines, as long as they are separated by commas.
+pub fn stream_ranges<'a, T: Id>(
+    lines: impl Iterator<Item = &'a str>,
+    radix: u32,
+) -> impl Iterator<Item = Result<Range<T>, ParseError>> {
+    lines
+        .flat_map(|ln| ln.split(','))
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(move |part| Range::parse_radix(part, radix))
 }
 
 /// Returns the number of digits in the given number.
 pub fn count_digits(n: u64) -> u32 {
-    n.ilog10() + 1
+    count_digits_in(n, 10)
+}
+
+/// Returns the number of digits in the given number when it's written in `radix`.
+pub fn count_digits_in(n: u64, radix: u32) -> u32 {
+    n.ilog(radix as u64) + 1
+}
+
+/// Writes the number in `radix`, using lowercase letters for digits above 9.
+pub fn to_radix_string(n: u128, radix: u32) -> String {
+    let mut n = n;
+    if n == 0 {
+        return "0".to_string();
+    }
+    let mut digits = vec![];
+    while n > 0 {
+        let digit = (n % radix as u128) as u32;
+        digits.push(char::from_digit(digit, radix).expect("Digit is always below the radix"));
+        n /= radix as u128;
+    }
+    digits.iter().rev().collect()
+}
+
+fn check_radix(radix: u32) -> Result<(), ParseError> {
+    if (2..=36).contains(&radix) {
+        Ok(())
+    } else {
+        Err(ParseError::InvalidRadix(radix))
+    }
+}
+
+/// The integer types that range bounds can be stored as.
+/// `u64` is plenty for real inputs and is what brute force works on, `u128` is for IDs past 10^19.
+pub trait Id: Copy + Ord + Debug + Display + Into<u128> + Send + Sync {
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseIntError>;
+}
+impl Id for u64 {
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseIntError> {
+        u64::from_str_radix(s, radix)
+    }
+}
+impl Id for u128 {
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseIntError> {
+        u128::from_str_radix(s, radix)
+    }
 }
 
 #[derive(Debug, Error)]
@@ -23,54 +157,283 @@ pub enum ParseError {
     #[error("Expected range to be in format 11-22 but found: {0}")]
     SplitRangeFailed(String),
     #[error(transparent)]
-    ParseIntError(#[from] std::num::ParseIntError),
+    ParseIntError(#[from] ParseIntError),
+    #[error("Radix must be between 2 and 36 but was {0}")]
+    InvalidRadix(u32),
+    #[error("Range starts at {start} which is after its end at {end}")]
+    StartAfterEnd { start: u128, end: u128 },
+    #[error("Expected merge or warn but found: {0}")]
+    UnknownOverlapPolicy(String),
+    #[error("Expected closed-form or scheduled but found: {0}")]
+    UnknownMethod(String),
+}
+
+/// How the parts add up the invalid IDs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// Build the invalid IDs directly, however big the ranges are.
+    ClosedForm,
+    /// Check every ID, letting a `schedule::Scheduler` spread the work over threads.
+    /// Only for IDs that fit in a `u64`.
+    Scheduled,
+}
+impl Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Method::ClosedForm => write!(f, "closed-form"),
+            Method::Scheduled => write!(f, "scheduled"),
+        }
+    }
+}
+impl FromStr for Method {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "closed-form" => Ok(Method::ClosedForm),
+            "scheduled" => Ok(Method::Scheduled),
+            _ => Err(ParseError::UnknownMethod(s.to_string())),
+        }
+    }
+}
+
+/// How a `ShopDatabase` deals with ranges that share IDs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    /// Merge overlapping ranges so that every ID is counted once.
+    Merge,
+    /// Keep the ranges as they are, so shared IDs count once per range, and log a warning for each overlap.
+    Warn,
+}
+impl Display for OverlapPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OverlapPolicy::Merge => write!(f, "merge"),
+            OverlapPolicy::Warn => write!(f, "warn"),
+        }
+    }
+}
+impl FromStr for OverlapPolicy {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "merge" => Ok(OverlapPolicy::Merge),
+            "warn" => Ok(OverlapPolicy::Warn),
+            _ => Err(ParseError::UnknownOverlapPolicy(s.to_string())),
+        }
+    }
+}
+
+/// Two ranges from the input that share at least one ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overlap<T = u64> {
+    pub first: Range<T>,
+    pub second: Range<T>,
+}
+impl<T: Id> Display for Overlap<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} overlaps {}", self.first, self.second)
+    }
 }
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct ShopDatabase {
-    pub ranges: Vec<Range>,
+pub struct ShopDatabase<T = u64> {
+    pub ranges: Vec<Range<T>>,
 }
-impl ShopDatabase {
-    pub fn new(ranges: &[Range]) -> Self {
+impl<T: Id> ShopDatabase<T> {
+    pub fn new(ranges: &[Range<T>]) -> Self {
         Self {
             ranges: ranges.to_vec(),
         }
     }
+    /// Every pair of ranges that share IDs, in order of where the overlap starts.
+    /// Each range is only compared against the one reaching furthest before it, so a range covering
+    /// several others overlaps each of them once.
+    pub fn overlaps(&self) -> Vec<Overlap<T>> {
+        let mut overlaps = vec![];
+        let mut furthest: Option<Range<T>> = None;
+        for range in self.sorted() {
+            match furthest {
+                Some(prev) if prev.radix == range.radix && range.start <= prev.end => {
+                    overlaps.push(Overlap {
+                        first: prev,
+                        second: range,
+                    });
+                    if range.end > prev.end {
+                        furthest = Some(range);
+                    }
+                }
+                _ => furthest = Some(range),
+            }
+        }
+        overlaps
+    }
+    /// The same IDs as a sorted list of ranges that don't overlap or touch, so that every ID is in exactly one.
+    pub fn normalized(&self) -> Self {
+        let mut ranges: Vec<Range<T>> = vec![];
+        for range in self.sorted() {
+            match ranges.last_mut() {
+                Some(last)
+                    if last.radix == range.radix
+                        && range.start.into() <= last.end.into().saturating_add(1) =>
+                {
+                    last.end = last.end.max(range.end);
+                }
+                _ => ranges.push(range),
+            }
+        }
+        Self { ranges }
+    }
+    /// Applies the overlap policy, merging the ranges or logging a warning for each overlap.
+    pub fn resolve_overlaps(self, policy: OverlapPolicy) -> Self {
+        match policy {
+            OverlapPolicy::Merge => self.normalized(),
+            OverlapPolicy::Warn => {
+                for overlap in self.overlaps() {
+                    log::warn!("{overlap}, shared IDs will be counted more than once");
+                }
+                self
+            }
+        }
+    }
+    fn sorted(&self) -> Vec<Range<T>> {
+        let mut ranges = self.ranges.clone();
+        ranges.sort_by_key(|range| (range.radix, range.start, range.end));
+        ranges
+    }
 }
-impl FromStr for ShopDatabase {
-    type Err = ParseError;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl<T: Id> ShopDatabase<T> {
+    /// Parses a database where the range bounds are written in `radix`.
+    pub fn parse_radix(s: &str, radix: u32) -> Result<Self, ParseError> {
         let range_parts = s.trim().split(',');
         let ranges = range_parts
-            .map(|part| part.parse::<Range>())
+            .map(|part| Range::parse_radix(part, radix))
             .collect::<Result<_, _>>()?;
         Ok(Self { ranges })
     }
 }
+impl FromStr for ShopDatabase {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_radix(s, 10)
+    }
+}
+
+/// A database stored with the narrowest IDs that fit, picked while parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnyShopDatabase {
+    /// Every bound fits in a `u64`, which is true of any real input.
+    Narrow(ShopDatabase<u64>),
+    Wide(ShopDatabase<u128>),
+}
+impl AnyShopDatabase {
+    /// Parses the database as `u64`s, falling back to `u128`s if any bound is too big.
+    pub fn parse_radix(s: &str, radix: u32) -> Result<Self, ParseError> {
+        match ShopDatabase::parse_radix(s, radix) {
+            Err(ParseError::ParseIntError(e)) if *e.kind() == IntErrorKind::PosOverflow => {
+                ShopDatabase::parse_radix(s, radix).map(AnyShopDatabase::Wide)
+            }
+            db => db.map(AnyShopDatabase::Narrow),
+        }
+    }
+    /// Stores the database as `u64`s if every bound fits, as it would have been if parsed that way.
+    pub fn narrowest(db: ShopDatabase<u128>) -> Self {
+        let narrow = db
+            .ranges
+            .iter()
+            .map(|range| {
+                Some(Range {
+                    start: u64::try_from(range.start).ok()?,
+                    end: u64::try_from(range.end).ok()?,
+                    radix: range.radix,
+                })
+            })
+            .collect::<Option<Vec<_>>>();
+        match narrow {
+            Some(ranges) => AnyShopDatabase::Narrow(ShopDatabase { ranges }),
+            None => AnyShopDatabase::Wide(db),
+        }
+    }
+    pub fn resolve_overlaps(self, policy: OverlapPolicy) -> Self {
+        match self {
+            AnyShopDatabase::Narrow(db) => AnyShopDatabase::Narrow(db.resolve_overlaps(policy)),
+            AnyShopDatabase::Wide(db) => AnyShopDatabase::Wide(db.resolve_overlaps(policy)),
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub struct Range {
-    start: u64,
-    end: u64,
+pub struct Range<T = u64> {
+    start: T,
+    end: T,
+    /// The base that IDs in this range are written in.
+    radix: u32,
 }
 impl Range {
+    /// A range of decimal IDs.
+    ///
+    /// Panics if `start` is after `end`, use `try_new` to get an error instead.
     pub fn new(start: u64, end: u64) -> Self {
-        Self { start, end }
+        assert!(start <= end, "Range starts at {start}, after its end {end}");
+        Self {
+            start,
+            end,
+            radix: 10,
+        }
+    }
+    /// Every invalid ID in the range, in ascending order, along with the block it is made of.
+    pub fn invalid_ids(&self) -> repeats::InvalidIds {
+        repeats::InvalidIds::new(self)
     }
 }
-impl FromStr for Range {
-    type Err = ParseError;
+impl<T: Id> Range<T> {
+    /// A range of IDs of any width, written in `radix`.
+    pub fn try_new(start: T, end: T, radix: u32) -> Result<Self, ParseError> {
+        check_radix(radix)?;
+        if start > end {
+            return Err(ParseError::StartAfterEnd {
+                start: start.into(),
+                end: end.into(),
+            });
+        }
+        Ok(Self { start, end, radix })
+    }
+    pub fn radix(&self) -> u32 {
+        self.radix
+    }
     // Input should be formed as two numbers seperated by a hyphen.
-    // e.g. 11-22 or 95-115
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    // e.g. 11-22 or 95-115, or 1f-3a in hex
+    pub fn parse_radix(s: &str, radix: u32) -> Result<Self, ParseError> {
+        check_radix(radix)?;
         let mut parts = s.split('-');
         let start = parts
             .next()
-            .ok_or(ParseError::SplitRangeFailed(s.to_string()))?
-            .parse::<u64>()?;
+            .ok_or(ParseError::SplitRangeFailed(s.to_string()))?;
         let end = parts
             .next()
-            .ok_or(ParseError::SplitRangeFailed(s.to_string()))?
-            .parse::<u64>()?;
-        Ok(Self { start, end })
+            .ok_or(ParseError::SplitRangeFailed(s.to_string()))?;
+        if parts.next().is_some() {
+            return Err(ParseError::SplitRangeFailed(s.to_string()));
+        }
+        Self::try_new(
+            T::from_str_radix(start, radix)?,
+            T::from_str_radix(end, radix)?,
+            radix,
+        )
+    }
+}
+impl FromStr for Range {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_radix(s, 10)
+    }
+}
+impl<T: Id> Display for Range<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            to_radix_string(self.start.into(), self.radix),
+            to_radix_string(self.end.into(), self.radix)
+        )
     }
 }
 
@@ -83,6 +446,10 @@ mod tests {
         let range = Range::from_str("11-22").unwrap();
         assert_eq!(range.start, 11);
         assert_eq!(range.end, 22);
+        assert!(matches!(
+            Range::from_str("1-2-3"),
+            Err(ParseError::SplitRangeFailed(_))
+        ));
     }
     // Just compare a minimal set, it doesn't need to be exhaustive.
     #[test]
@@ -94,4 +461,168 @@ mod tests {
         assert_eq!(db.ranges[1], Range::new(95, 115));
         assert_eq!(db.ranges[2], Range::new(998, 1012));
     }
+    #[test]
+    fn test_parse_range_radix() {
+        let range = Range::<u64>::parse_radix("1f-3A", 16).unwrap();
+        assert_eq!((range.start, range.end, range.radix()), (31, 58, 16));
+        let range = Range::<u64>::parse_radix("101-111", 2).unwrap();
+        assert_eq!((range.start, range.end), (5, 7));
+        assert!(matches!(
+            Range::<u64>::parse_radix("12-13", 2),
+            Err(ParseError::ParseIntError(_))
+        ));
+        assert!(matches!(
+            Range::<u64>::parse_radix("1-2", 37),
+            Err(ParseError::InvalidRadix(37))
+        ));
+    }
+    #[test]
+    fn test_start_after_end() {
+        assert!(matches!(
+            Range::from_str("22-11"),
+            Err(ParseError::StartAfterEnd { start: 22, end: 11 })
+        ));
+        assert!(ShopDatabase::from_str("11-22,5-4").is_err());
+    }
+    #[test]
+    #[should_panic(expected = "Range starts at 5, after its end 4")]
+    fn test_new_start_after_end() {
+        Range::new(5, 4);
+    }
+    #[test]
+    fn test_normalized() {
+        let db = ShopDatabase::from_str("95-115,11-22,20-30,11-22,31-40,50-60").unwrap();
+        let expected = [Range::new(11, 40), Range::new(50, 60), Range::new(95, 115)];
+        assert_eq!(db.normalized().ranges, expected);
+    }
+    #[test]
+    fn test_overlaps() {
+        let db = ShopDatabase::from_str("1-100,95-115,11-22,20-30,31-40").unwrap();
+        let overlaps = db
+            .overlaps()
+            .iter()
+            .map(|o| o.to_string())
+            .collect::<Vec<_>>();
+        let expected = [
+            "1-100 overlaps 11-22",
+            "1-100 overlaps 20-30",
+            "1-100 overlaps 31-40",
+            "1-100 overlaps 95-115",
+        ];
+        assert_eq!(overlaps, expected);
+        // Touching ranges don't share any IDs.
+        let db = ShopDatabase::from_str("11-22,23-30").unwrap();
+        assert!(db.overlaps().is_empty());
+    }
+    #[test]
+    fn test_overlap_policy() {
+        let db = ShopDatabase::from_str("11-22,11-22").unwrap();
+        let sum = |db: &ShopDatabase| part_2::sum_all_invalid_in_db_closed_form(db).unwrap();
+        assert_eq!(sum(&db), 66);
+        assert_eq!(sum(&db.clone().resolve_overlaps(OverlapPolicy::Merge)), 33);
+        assert_eq!(sum(&db.resolve_overlaps(OverlapPolicy::Warn)), 66);
+        assert_eq!(
+            "Warn".parse::<OverlapPolicy>().unwrap(),
+            OverlapPolicy::Warn
+        );
+        assert!("skip".parse::<OverlapPolicy>().is_err());
+    }
+    #[test]
+    fn test_streaming_resolves_overlaps() {
+        for (policy, part_1, part_2) in [("merge", 33, 33), ("warn", 66, 66)] {
+            let params =
+                Params::new(PARAMS, vec![("overlaps".to_string(), policy.to_string())]).unwrap();
+            let input = "11-22,11-22";
+            assert_eq!(run_part_1(input, &params).unwrap(), part_1, "{policy}");
+            assert_eq!(
+                stream_part_1(input.lines(), &params).unwrap(),
+                part_1,
+                "{policy}"
+            );
+            assert_eq!(run_part_2(input, &params).unwrap(), part_2, "{policy}");
+            assert_eq!(
+                stream_part_2(input.lines(), &params).unwrap(),
+                part_2,
+                "{policy}"
+            );
+        }
+    }
+    #[test]
+    fn test_scheduled_method() {
+        let input = include_str!("../inputs/test.txt");
+        for threads in ["0", "1", "3"] {
+            let params = Params::new(
+                PARAMS,
+                vec![
+                    ("method".to_string(), "scheduled".to_string()),
+                    ("threads".to_string(), threads.to_string()),
+                ],
+            )
+            .unwrap();
+            assert_eq!(run_part_1(input, &params).unwrap(), 1227775554);
+            assert_eq!(stream_part_1(input.lines(), &params).unwrap(), 1227775554);
+            assert_eq!(run_part_2(input, &params).unwrap(), 4174379265);
+            assert_eq!(stream_part_2(input.lines(), &params).unwrap(), 4174379265);
+        }
+        assert_eq!("Closed-Form".parse::<Method>().unwrap(), Method::ClosedForm);
+        assert!("brute".parse::<Method>().is_err());
+    }
+    #[test]
+    fn test_scheduled_method_needs_narrow_ids() {
+        let params = Params::new(
+            PARAMS,
+            vec![("method".to_string(), "scheduled".to_string())],
+        )
+        .unwrap();
+        let input = "11-22,100000000000000000000-100000000000000000001";
+        assert!(run_part_1(input, &params).is_err());
+        assert!(stream_part_2(input.lines(), &params).is_err());
+    }
+    #[test]
+    fn test_narrowest() {
+        let wide = |ranges: &[(u128, u128)]| ShopDatabase {
+            ranges: ranges
+                .iter()
+                .map(|&(start, end)| Range::try_new(start, end, 10).unwrap())
+                .collect(),
+        };
+        assert_eq!(
+            AnyShopDatabase::narrowest(wide(&[(11, 22), (95, 115)])),
+            AnyShopDatabase::Narrow(ShopDatabase {
+                ranges: vec![Range::new(11, 22), Range::new(95, 115)]
+            })
+        );
+        let db = wide(&[(11, 22), (1 << 64, 1 << 65)]);
+        assert_eq!(
+            AnyShopDatabase::narrowest(db.clone()),
+            AnyShopDatabase::Wide(db)
+        );
+    }
+    #[test]
+    fn test_to_radix_string() {
+        assert_eq!(to_radix_string(0, 2), "0");
+        assert_eq!(to_radix_string(255, 16), "ff");
+        assert_eq!(to_radix_string(10, 2), "1010");
+        assert_eq!(to_radix_string(35, 36), "z");
+    }
+    #[test]
+    fn test_count_digits_in() {
+        assert_eq!(count_digits_in(1, 2), 1);
+        assert_eq!(count_digits_in(8, 2), 4);
+        assert_eq!(count_digits_in(255, 16), 2);
+        assert_eq!(count_digits_in(256, 16), 3);
+    }
+    #[test]
+    fn test_stream_ranges() {
+        let lines = ["11-22,95-115,", "998-1012", ""];
+        let ranges = stream_ranges(lines.into_iter(), 10)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let expected = vec![
+            Range::new(11, 22),
+            Range::new(95, 115),
+            Range::new(998, 1012),
+        ];
+        assert_eq!(ranges, expected);
+    }
 }
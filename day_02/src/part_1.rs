@@ -5,38 +5,100 @@ use thiserror::Error;
 pub enum PartError {
     #[error(transparent)]
     ParseError(#[from] ParseError),
+    #[error(transparent)]
+    Overflow(#[from] repeats::SumOverflow),
+    #[error("The scheduled method checks every ID, so it only works when they all fit in a u64")]
+    TooWideToSchedule,
+}
+pub fn run(input: &str, radix: u32, overlaps: OverlapPolicy) -> Result<u128, PartError> {
+    let sum = match AnyShopDatabase::parse_radix(input, radix)?.resolve_overlaps(overlaps) {
+        AnyShopDatabase::Narrow(db) => sum_invalid_all_ranges(&db)?,
+        AnyShopDatabase::Wide(db) => sum_invalid_all_ranges(&db)?,
+    };
+    Ok(sum)
+}
+/// Same as `run`, but the ranges are parsed straight from the lines without loading the whole input.
+/// The ranges themselves are still collected, since overlaps can only be resolved once they're all known.
+pub fn run_streaming<'a>(
+    lines: impl Iterator<Item = &'a str>,
+    radix: u32,
+    overlaps: OverlapPolicy,
+) -> Result<u128, PartError> {
+    // Every range is read as `u128`, as there's no going back to widen the ones already parsed.
+    let ranges = stream_ranges::<u128>(lines, radix).collect::<Result<_, _>>()?;
+    let db = ShopDatabase { ranges }.resolve_overlaps(overlaps);
+    Ok(sum_invalid_all_ranges(&db)?)
+}
+/// Same as `run`, but checks every ID, spread over threads by the scheduler once there are enough of them.
+pub fn run_scheduled(
+    input: &str,
+    radix: u32,
+    overlaps: OverlapPolicy,
+    scheduler: &schedule::Scheduler,
+) -> Result<u128, PartError> {
+    let db = AnyShopDatabase::parse_radix(input, radix)?.resolve_overlaps(overlaps);
+    sum_scheduled(db, scheduler)
+}
+/// Same as `run_streaming`, but checks every ID like `run_scheduled`.
+pub fn run_streaming_scheduled<'a>(
+    lines: impl Iterator<Item = &'a str>,
+    radix: u32,
+    overlaps: OverlapPolicy,
+    scheduler: &schedule::Scheduler,
+) -> Result<u128, PartError> {
+    let ranges = stream_ranges::<u128>(lines, radix).collect::<Result<_, _>>()?;
+    let db = AnyShopDatabase::narrowest(ShopDatabase { ranges }).resolve_overlaps(overlaps);
+    sum_scheduled(db, scheduler)
+}
+fn sum_scheduled(db: AnyShopDatabase, scheduler: &schedule::Scheduler) -> Result<u128, PartError> {
+    match db {
+        AnyShopDatabase::Narrow(db) => Ok(sum_invalid_all_ranges_scheduled(&db, scheduler) as u128),
+        AnyShopDatabase::Wide(_) => Err(PartError::TooWideToSchedule),
+    }
+}
+/// The invalid IDs in the range, which for this part are the ones made of a block repeated exactly twice.
+pub fn invalid_ids(range: &Range) -> impl Iterator<Item = repeats::InvalidId> {
+    range.invalid_ids().with_repeats(2)
+}
+pub fn sum_invalid_all_ranges<T: Id>(db: &ShopDatabase<T>) -> Result<u128, repeats::SumOverflow> {
+    db.ranges.iter().try_fold(0u128, |sum, range| {
+        sum.checked_add(sum_invalid(range)?)
+            .ok_or(repeats::SumOverflow)
+    })
 }
-pub fn run(input: &str) -> Result<u64, PartError> {
-    let db = input.parse::<ShopDatabase>()?;
-    Ok(sum_invalid_all_ranges(&db))
+/// Sums the invalid IDs in the range by building them directly rather than checking every number.
+pub fn sum_invalid<T: Id>(range: &Range<T>) -> Result<u128, repeats::SumOverflow> {
+    repeats::sum_repeated_twice(range)
 }
-pub fn sum_invalid_all_ranges(db: &ShopDatabase) -> u64 {
-    db.ranges.iter().map(|range| sum_invalid(range)).sum()
+/// Checks every number like `sum_invalid_brute_force`, letting the scheduler decide whether to use threads.
+pub fn sum_invalid_all_ranges_scheduled(db: &ShopDatabase, scheduler: &schedule::Scheduler) -> u64 {
+    scheduler.sum(db, sum_invalid_brute_force)
 }
-pub fn sum_invalid(range: &Range) -> u64 {
+/// Sums the invalid IDs in the range by checking every number in it.
+pub fn sum_invalid_brute_force(range: &Range) -> u64 {
     let mut sum = 0;
     for i in range.start..=range.end {
-        if is_number_invalid(i) {
+        if is_number_invalid(i, range.radix) {
             sum += i;
         }
     }
     sum
 }
-fn is_number_invalid(n: u64) -> bool {
+fn is_number_invalid(n: u64, radix: u32) -> bool {
     // Get the number of digits in the number.
-    let len = count_digits(n);
+    let len = count_digits_in(n, radix);
     // Only even numbers can be invalid according to the problem statement.
-    if len % 2 == 0 {
-        let (left, right) = split_number(n, len);
+    if len.is_multiple_of(2) {
+        let (left, right) = split_number(n, len, radix);
         // Check if the left and right parts are equal.
-        return left == right;
+        left == right
     } else {
         false
     }
 }
-fn split_number(n: u64, l: u32) -> (u64, u64) {
-    let left = n / 10_u64.pow(l / 2);
-    let right = n % 10_u64.pow(l / 2);
+fn split_number(n: u64, l: u32, radix: u32) -> (u64, u64) {
+    let left = n / (radix as u64).pow(l / 2);
+    let right = n % (radix as u64).pow(l / 2);
     (left, right)
 }
 
@@ -68,29 +130,73 @@ mod tests {
     }
     #[test]
     fn test_split_number() {
-        assert_eq!(split_number(1234, count_digits(1234)), (12, 34));
-        assert_eq!(split_number(12345, count_digits(12345)), (123, 45));
-        assert_eq!(split_number(38593859, count_digits(38593859)), (3859, 3859));
+        assert_eq!(split_number(1234, count_digits(1234), 10), (12, 34));
+        assert_eq!(split_number(12345, count_digits(12345), 10), (123, 45));
+        assert_eq!(
+            split_number(38593859, count_digits(38593859), 10),
+            (3859, 3859)
+        );
+        // 0b1010 is 10 twice.
+        assert_eq!(split_number(10, 4, 2), (2, 2));
     }
     #[test]
     fn test_sum_invalid() {
         let range = Range::new(11, 22);
         let expected = 11 + 22;
-        let actual = sum_invalid(&range);
+        let actual = sum_invalid(&range).unwrap();
         assert_eq!(actual, expected);
     }
     #[test]
     fn test_sum_invalid_no_candidates() {
         let range = Range::new(1698522, 1698528);
         let expected = 0;
-        let actual = sum_invalid(&range);
+        let actual = sum_invalid(&range).unwrap();
         assert_eq!(actual, expected);
     }
     #[test]
+    fn test_sum_invalid_matches_brute_force() {
+        for range in setup_test_database().ranges {
+            assert_eq!(
+                sum_invalid(&range).unwrap(),
+                sum_invalid_brute_force(&range) as u128
+            );
+        }
+    }
+    #[test]
+    fn test_invalid_ids_database() {
+        let db = setup_test_database();
+        let sum: u64 = db.ranges.iter().flat_map(invalid_ids).map(|id| id.id).sum();
+        assert_eq!(sum, 1227775554);
+    }
+    #[test]
     fn test_sum_invalid_database() {
         let db = setup_test_database();
         let expected = 1227775554;
-        let actual = sum_invalid_all_ranges(&db);
+        let actual = sum_invalid_all_ranges(&db).unwrap();
         assert_eq!(actual, expected);
     }
+    #[test]
+    fn test_sums_past_u64() {
+        // The sum of the 20 digit repeats that fit in a u64 doesn't fit in one itself.
+        let sum = run(
+            "10000000000000000000-18446744073709551615",
+            10,
+            OverlapPolicy::Merge,
+        )
+        .unwrap();
+        assert!(sum > u64::MAX as u128);
+        // Nor do the bounds here, so the database is read as u128s.
+        let sum = run(
+            "123451234512345123450-123451234512345123451",
+            10,
+            OverlapPolicy::Merge,
+        );
+        assert_eq!(sum.unwrap(), 0);
+        let sum = run(
+            "1234567890123456789012345678901234567890-12345678901234567890123456789012345678901",
+            10,
+            OverlapPolicy::Merge,
+        );
+        assert!(matches!(sum, Err(PartError::ParseError(_))));
+    }
 }
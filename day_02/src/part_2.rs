@@ -8,44 +8,105 @@ use thiserror::Error;
 pub enum PartError {
     #[error(transparent)]
     ParseError(#[from] ParseError),
+    #[error(transparent)]
+    Overflow(#[from] repeats::SumOverflow),
+    #[error("The scheduled method checks every ID, so it only works when they all fit in a u64")]
+    TooWideToSchedule,
+}
+pub fn run(input: &str, radix: u32, overlaps: OverlapPolicy) -> Result<u128, PartError> {
+    let sum = match AnyShopDatabase::parse_radix(input, radix)?.resolve_overlaps(overlaps) {
+        AnyShopDatabase::Narrow(db) => sum_all_invalid_in_db_closed_form(&db)?,
+        AnyShopDatabase::Wide(db) => sum_all_invalid_in_db_closed_form(&db)?,
+    };
+    Ok(sum)
+}
+/// Same as `run`, but the ranges are parsed straight from the lines without loading the whole input.
+/// The ranges themselves are still collected, since overlaps can only be resolved once they're all known.
+pub fn run_streaming<'a>(
+    lines: impl Iterator<Item = &'a str>,
+    radix: u32,
+    overlaps: OverlapPolicy,
+) -> Result<u128, PartError> {
+    // Every range is read as `u128`, as there's no going back to widen the ones already parsed.
+    let ranges = stream_ranges::<u128>(lines, radix).collect::<Result<_, _>>()?;
+    let db = ShopDatabase { ranges }.resolve_overlaps(overlaps);
+    Ok(sum_all_invalid_in_db_closed_form(&db)?)
+}
+/// Same as `run`, but checks every ID, spread over threads by the scheduler once there are enough of them.
+pub fn run_scheduled(
+    input: &str,
+    radix: u32,
+    overlaps: OverlapPolicy,
+    scheduler: &schedule::Scheduler,
+) -> Result<u128, PartError> {
+    let db = AnyShopDatabase::parse_radix(input, radix)?.resolve_overlaps(overlaps);
+    sum_scheduled(db, scheduler)
+}
+/// Same as `run_streaming`, but checks every ID like `run_scheduled`.
+pub fn run_streaming_scheduled<'a>(
+    lines: impl Iterator<Item = &'a str>,
+    radix: u32,
+    overlaps: OverlapPolicy,
+    scheduler: &schedule::Scheduler,
+) -> Result<u128, PartError> {
+    let ranges = stream_ranges::<u128>(lines, radix).collect::<Result<_, _>>()?;
+    let db = AnyShopDatabase::narrowest(ShopDatabase { ranges }).resolve_overlaps(overlaps);
+    sum_scheduled(db, scheduler)
+}
+fn sum_scheduled(db: AnyShopDatabase, scheduler: &schedule::Scheduler) -> Result<u128, PartError> {
+    match db {
+        AnyShopDatabase::Narrow(db) => Ok(sum_all_invalid_in_db_scheduled(&db, scheduler) as u128),
+        AnyShopDatabase::Wide(_) => Err(PartError::TooWideToSchedule),
+    }
 }
-pub fn run(input: &str) -> Result<u64, PartError> {
-    let db = input.parse::<ShopDatabase>()?;
-    Ok(sum_all_invalid_in_db_multi(&db))
+/// The invalid IDs in the range, which for this part are the ones made of a block repeated any number of times.
+pub fn invalid_ids(range: &Range) -> repeats::InvalidIds {
+    range.invalid_ids()
 }
 // FOR COMPARISONS BETWEEN SINGLE AND MULTI THREADING
 // According to benchmarks (see the benches folder), the single-threaded version
 // is faster than the multi-threaded version. The test input was used so I guess it is still
-// kind of small.
+// kind of small. `sum_all_invalid_in_db_scheduled` only uses threads once there's enough work.
 pub fn sum_all_invalid_in_db_multi(db: &ShopDatabase) -> u64 {
-    db.ranges
-        .iter()
-        .map(|range| sum_all_invalid_in_range_multi(range))
-        .sum()
+    db.ranges.iter().map(sum_all_invalid_in_range_multi).sum()
 }
 pub fn sum_all_invalid_in_db_single(db: &ShopDatabase) -> u64 {
-    db.ranges
-        .iter()
-        .map(|range| sum_all_invalid_in_range_single(range))
-        .sum()
+    db.ranges.iter().map(sum_all_invalid_in_range_single).sum()
+}
+/// Checks every number like `sum_all_invalid_in_db_single`, letting the scheduler decide whether to use threads.
+pub fn sum_all_invalid_in_db_scheduled(db: &ShopDatabase, scheduler: &schedule::Scheduler) -> u64 {
+    scheduler.sum(db, sum_all_invalid_in_range_single)
+}
+/// Builds the invalid IDs directly instead of checking every number, so the size of the ranges doesn't matter.
+pub fn sum_all_invalid_in_db_closed_form<T: Id>(
+    db: &ShopDatabase<T>,
+) -> Result<u128, repeats::SumOverflow> {
+    db.ranges.iter().try_fold(0u128, |sum, range| {
+        sum.checked_add(sum_all_invalid_in_range_closed_form(range)?)
+            .ok_or(repeats::SumOverflow)
+    })
+}
+pub fn sum_all_invalid_in_range_closed_form<T: Id>(
+    range: &Range<T>,
+) -> Result<u128, repeats::SumOverflow> {
+    repeats::sum_repeated(range)
 }
 pub fn sum_all_invalid_in_range_single(range: &Range) -> u64 {
     (range.start..=range.end)
-        .into_iter()
-        .filter(|&n| is_number_invalid(n))
+        .filter(|&n| is_number_invalid(n, range.radix))
         .sum()
 }
 pub fn sum_all_invalid_in_range_multi(range: &Range) -> u64 {
     (range.start..=range.end)
         .into_par_iter()
-        .filter(|&n| is_number_invalid(n))
+        .filter(|&n| is_number_invalid(n, range.radix))
         .sum()
 }
-pub fn is_number_invalid(n: u64) -> bool {
-    let len = count_digits(n);
+pub fn is_number_invalid(n: u64, radix: u32) -> bool {
+    let len = count_digits_in(n, radix);
     let factors = get_factors(len as u64);
     for number_of_segments in factors {
-        let segments = split_number(n, len, number_of_segments);
+        let segments = split_number(n, len, number_of_segments, radix);
         info!("n: {}, f: {}, segs: {:?}", n, number_of_segments, segments);
         if segments.iter().all_equal() {
             return true;
@@ -63,7 +124,7 @@ pub fn get_factors(n: u64) -> Vec<u32> {
     // Calculate the square root of the number.
     let sqrt = n.isqrt();
     for i in 2..=sqrt {
-        if n % i == 0 {
+        if n.is_multiple_of(i) {
             factors.push(i as u32);
             if i != n / i {
                 factors.push((n / i) as u32);
@@ -74,13 +135,14 @@ pub fn get_factors(n: u64) -> Vec<u32> {
     info!("Factors of {} are {:?}", n, factors);
     factors
 }
-fn split_number(n: u64, l: u32, s: u32) -> Vec<u64> {
+fn split_number(n: u64, l: u32, s: u32, radix: u32) -> Vec<u64> {
     let mut n = n;
     let mut v = vec![0; s as usize];
-    for i in 0..s as usize {
-        let p1 = n / 10_u64.pow(l / s);
-        let p2 = n % 10_u64.pow(l / s);
-        v[i] = p2;
+    let segment_size = (radix as u64).pow(l / s);
+    for segment in v.iter_mut() {
+        let p1 = n / segment_size;
+        let p2 = n % segment_size;
+        *segment = p2;
         n = p1;
     }
     v
@@ -157,7 +219,7 @@ mod tests {
         let l = 6;
         let s = 2;
         let expected = vec![123, 456];
-        let mut result = split_number(n, l, s);
+        let mut result = split_number(n, l, s, 10);
         result.sort_unstable(); // Just for the sake of comparison, order does not really matter otherwise.
         assert_eq!(result, expected);
     }
@@ -174,7 +236,7 @@ mod tests {
         ];
 
         for (n, expected) in test_pairs {
-            let result = is_number_invalid(n);
+            let result = is_number_invalid(n, 10);
             assert_eq!(
                 result, expected,
                 "Failed for n = {}, expected = {}",
@@ -208,6 +270,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_sum_all_invalid_numbers_in_db_closed_form() {
+        let db = setup_test_database();
+        let expected = 4174379265; // From website.
+        assert_eq!(sum_all_invalid_in_db_closed_form(&db).unwrap(), expected);
+        let wide = ShopDatabase::<u128>::parse_radix("11-22,95-115,998-1012", 10).unwrap();
+        assert_eq!(
+            sum_all_invalid_in_db_closed_form(&wide).unwrap(),
+            33 + 210 + 2009
+        );
+    }
+
+    #[test]
+    fn test_invalid_ids_in_db() {
+        let db = setup_test_database();
+        let sum: u64 = db.ranges.iter().flat_map(invalid_ids).map(|id| id.id).sum();
+        assert_eq!(sum, 4174379265);
+    }
+
+    #[test]
+    fn test_sum_all_invalid_numbers_in_db_scheduled() {
+        let db = setup_test_database();
+        let scheduler = schedule::Scheduler::new().parallel_threshold(0);
+        assert_eq!(sum_all_invalid_in_db_scheduled(&db, &scheduler), 4174379265);
+    }
+
     #[ignore]
     #[test]
     fn test_sum_all_invalid_numbers_in_db() {
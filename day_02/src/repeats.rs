@@ -0,0 +1,396 @@
+//! Closed form sums of invalid IDs, without looking at every number in a range.
+//!
+//! A number with `len` digits made of a `period` digit block repeated is the block times a multiplier
+//! made of 1s spaced `period` apart, e.g. `123123123 = 123 * 1001001`. So every such number in a range
+//! comes from a contiguous run of blocks, and their sum is the multiplier times an arithmetic series.
+//! The same holds in any radix, with the multiplier's 1s spaced `period` digits of that radix apart.
+//!
+//! Sums are worked out in `u128` whatever the width of the range, and come back as `SumOverflow` rather
+//! than wrapping if even that isn't enough.
+
+use std::fmt::Display;
+
+use thiserror::Error;
+
+use crate::{Id, Range, count_digits_in, to_radix_string};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("The sum of the invalid IDs is too big for a u128")]
+pub struct SumOverflow;
+
+/// The number that turns a `period` digit block into `len` digits of it repeated, e.g. 1001001 for 3 and 9
+/// in decimal. `period` must divide `len`, and `len` digits must fit in a `u128`.
+pub fn repeat_multiplier(len: u32, period: u32, radix: u32) -> u128 {
+    // Built up one block at a time, as `radix^len` itself may not fit.
+    let shift = (radix as u128).pow(period);
+    (0..len / period).fold(0, |multiplier, _| multiplier * shift + 1)
+}
+
+/// Sums every number between `lo` and `hi` inclusive that has `len` digits and is a `period` digit block
+/// repeated, with digits in `radix`. `lo` and `hi` must both have `len` digits.
+pub fn sum_with_period(
+    lo: u128,
+    hi: u128,
+    len: u32,
+    period: u32,
+    radix: u32,
+) -> Result<u128, SumOverflow> {
+    let multiplier = repeat_multiplier(len, period, radix);
+    // Blocks can't start with a 0, otherwise the number would have fewer digits.
+    let first = (radix as u128).pow(period - 1).max(lo.div_ceil(multiplier));
+    let last = ((radix as u128).pow(period) - 1).min(hi / multiplier);
+    if first > last {
+        return Ok(0);
+    }
+    // Halve whichever of the count and the sum of the ends is even before multiplying, to leave more room.
+    let count = last - first + 1;
+    let ends = first.checked_add(last).ok_or(SumOverflow)?;
+    let series = if count.is_multiple_of(2) {
+        (count / 2).checked_mul(ends)
+    } else {
+        count.checked_mul(ends / 2)
+    };
+    series
+        .and_then(|series| series.checked_mul(multiplier))
+        .ok_or(SumOverflow)
+}
+
+/// Sums the numbers in the range made of a block repeated exactly twice, as in part 1.
+pub fn sum_repeated_twice<T: Id>(range: &Range<T>) -> Result<u128, SumOverflow> {
+    let mut sum = 0u128;
+    for (lo, hi, len) in split_by_length(range).filter(|(_, _, len)| len.is_multiple_of(2)) {
+        let part = sum_with_period(lo, hi, len, len / 2, range.radix)?;
+        sum = sum.checked_add(part).ok_or(SumOverflow)?;
+    }
+    Ok(sum)
+}
+
+/// Sums the numbers in the range made of a block repeated at least twice, as in part 2.
+///
+/// A number that repeats with some period also repeats with every multiple of it that divides its length
+/// (`111111` is `1` six times, `11` three times and `111` twice), so summing every period would count it
+/// more than once. Working up through the periods, the numbers whose shortest period is exactly `p` are the
+/// ones with period `p` less the ones whose shortest period is a smaller divisor of `p`.
+pub fn sum_repeated<T: Id>(range: &Range<T>) -> Result<u128, SumOverflow> {
+    let mut sum = 0u128;
+    for (lo, hi, len) in split_by_length(range) {
+        let periods = (1..len)
+            .filter(|p| len.is_multiple_of(*p))
+            .collect::<Vec<_>>();
+        // The sum of the numbers whose shortest period is exactly `periods[i]`.
+        let mut exact = Vec::with_capacity(periods.len());
+        for &period in &periods {
+            // Never more than the sum for `period`, so it can't overflow if that didn't.
+            let shorter: u128 = periods
+                .iter()
+                .zip(&exact)
+                .filter(|(p, _)| period.is_multiple_of(**p) && **p < period)
+                .map(|(_, sum)| sum)
+                .sum();
+            exact.push(sum_with_period(lo, hi, len, period, range.radix)? - shorter);
+        }
+        for part in exact {
+            sum = sum.checked_add(part).ok_or(SumOverflow)?;
+        }
+    }
+    Ok(sum)
+}
+
+/// An invalid ID along with the shortest block it is a repeat of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidId {
+    pub id: u64,
+    /// The shortest block of digits that the ID is made of, e.g. 56 for 565656.
+    pub block: u64,
+    /// How many times the block is repeated, e.g. 3 for 565656.
+    pub repeats: u32,
+    /// The base the ID is written in, which decides what counts as a block.
+    pub radix: u32,
+}
+impl InvalidId {
+    /// Whether the ID can be read as exactly `k` copies of some block.
+    /// That's the case whenever `k` divides the number of repeats of the shortest block,
+    /// so 1111 counts as 2 repeats of 11 as well as 4 repeats of 1.
+    pub fn splits_into(&self, k: u32) -> bool {
+        k > 0 && self.repeats.is_multiple_of(k)
+    }
+}
+impl Display for InvalidId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} = {} x{}",
+            to_radix_string(self.id.into(), self.radix),
+            to_radix_string(self.block.into(), self.radix),
+            self.repeats
+        )
+    }
+}
+
+/// Yields every invalid ID in a range in ascending order, jumping straight from one to the next.
+/// Made with `Range::invalid_ids`.
+#[derive(Debug, Clone)]
+pub struct InvalidIds {
+    /// The smallest number that hasn't been looked at yet, `None` once past the end of the range.
+    next: Option<u64>,
+    end: u64,
+    radix: u32,
+}
+impl InvalidIds {
+    pub(crate) fn new(range: &Range) -> Self {
+        InvalidIds {
+            next: Some(range.start.max(1)),
+            end: range.end,
+            radix: range.radix,
+        }
+    }
+    /// Only the IDs that can be read as exactly `k` copies of some block. Part 1 is `with_repeats(2)`.
+    pub fn with_repeats(self, k: u32) -> impl Iterator<Item = InvalidId> {
+        self.filter(move |id| id.splits_into(k))
+    }
+}
+impl Iterator for InvalidIds {
+    type Item = InvalidId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let n = self.next.filter(|&n| n <= self.end)?;
+            let len = count_digits_in(n, self.radix);
+            // The smallest repeat of any period that is at least `n` and still has `len` digits.
+            let candidate = (1..len)
+                .filter(|p| len.is_multiple_of(*p))
+                .filter_map(|period| smallest_with_period(n, len, period, self.radix))
+                .min();
+            match candidate {
+                Some(id) if id <= self.end => {
+                    self.next = id.checked_add(1);
+                    return Some(describe(id, len, self.radix));
+                }
+                Some(_) => self.next = None,
+                // Nothing else with this many digits, so carry on from the first number with more.
+                None => self.next = (self.radix as u64).checked_pow(len),
+            }
+        }
+    }
+}
+
+/// The smallest number that is at least `n`, has `len` digits and is a `period` digit block repeated.
+fn smallest_with_period(n: u64, len: u32, period: u32, radix: u32) -> Option<u64> {
+    let multiplier = repeat_multiplier(len, period, radix);
+    let block = (radix as u128)
+        .pow(period - 1)
+        .max((n as u128).div_ceil(multiplier));
+    if block >= (radix as u128).pow(period) {
+        return None;
+    }
+    // The top few 20 digit repeats are too big for a u64.
+    u64::try_from(block * multiplier).ok()
+}
+
+/// Finds the shortest block of an ID with `len` digits that is known to be a repeat.
+fn describe(id: u64, len: u32, radix: u32) -> InvalidId {
+    let period = (1..len)
+        .filter(|p| len.is_multiple_of(*p))
+        .find(|&p| (id as u128).is_multiple_of(repeat_multiplier(len, p, radix)))
+        .expect("Only called with repeats");
+    InvalidId {
+        id,
+        block: (id as u128 / repeat_multiplier(len, period, radix)) as u64,
+        repeats: len / period,
+        radix,
+    }
+}
+
+/// Splits a range up into the parts where every number has the same number of digits.
+/// Yields `(lo, hi, len)` for each part.
+fn split_by_length<T: Id>(range: &Range<T>) -> impl Iterator<Item = (u128, u128, u32)> {
+    // 0 can't be a repeat of anything and has no digits to count.
+    let start = range.start.into().max(1);
+    let end = range.end.into();
+    let radix = range.radix as u128;
+    let lengths = (start <= end).then(|| start.ilog(radix) + 1..=end.ilog(radix) + 1);
+    lengths.into_iter().flatten().map(move |len| {
+        let lo = start.max(radix.pow(len - 1));
+        let hi = end.min(radix.checked_pow(len).map_or(u128::MAX, |p| p - 1));
+        (lo, hi, len)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{part_1, part_2};
+
+    #[test]
+    fn test_repeat_multiplier() {
+        assert_eq!(repeat_multiplier(9, 3, 10), 1001001);
+        assert_eq!(repeat_multiplier(6, 1, 10), 111111);
+        assert_eq!(repeat_multiplier(4, 2, 10), 101);
+        assert_eq!(repeat_multiplier(4, 2, 16), 0x101);
+        assert_eq!(repeat_multiplier(6, 2, 2), 0b10101);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let ranges = [
+            Range::new(1, 10000),
+            Range::new(95, 115),
+            Range::new(998, 1012),
+            Range::new(100000, 200000),
+            Range::new(565653, 565659),
+            Range::new(1188511880, 1188511890),
+            // An empty range, built directly since `Range::new` won't make one.
+            Range {
+                start: 5,
+                end: 4,
+                radix: 10,
+            },
+        ];
+        for range in ranges {
+            assert_eq!(
+                sum_repeated_twice(&range).unwrap(),
+                part_1::sum_invalid_brute_force(&range) as u128,
+                "{range:?}"
+            );
+            assert_eq!(
+                sum_repeated(&range).unwrap(),
+                part_2::sum_all_invalid_in_range_single(&range) as u128,
+                "{range:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_huge_range() {
+        // Far too many numbers to check one at a time.
+        let range = Range::new(1, 10u64.pow(15));
+        // Only the 2, 4, ..., 14 digit numbers can repeat twice, and each one is its block times 10^k + 1.
+        let expected_twice: u128 = (1..=7)
+            .map(|k| {
+                let (first, last) = (10u128.pow(k - 1), 10u128.pow(k) - 1);
+                (10u128.pow(k) + 1) * (first + last) * (last - first + 1) / 2
+            })
+            .sum();
+        assert_eq!(sum_repeated_twice(&range).unwrap(), expected_twice);
+        assert!(sum_repeated(&range).unwrap() > expected_twice);
+    }
+
+    #[test]
+    fn test_invalid_ids_structure() {
+        let ids = Range::new(565653, 565659).invalid_ids().collect::<Vec<_>>();
+        let expected = InvalidId {
+            id: 565656,
+            block: 56,
+            repeats: 3,
+            radix: 10,
+        };
+        assert_eq!(ids, vec![expected]);
+        assert_eq!(expected.to_string(), "565656 = 56 x3");
+
+        let ids = Range::new(1000, 1111).invalid_ids().collect::<Vec<_>>();
+        let summary = ids
+            .iter()
+            .map(|id| (id.id, id.block, id.repeats))
+            .collect::<Vec<_>>();
+        assert_eq!(summary, vec![(1010, 10, 2), (1111, 1, 4)]);
+        assert!(ids[1].splits_into(2));
+        assert!(!ids[1].splits_into(3));
+    }
+
+    #[test]
+    fn test_invalid_ids_match_brute_force() {
+        let range = Range::new(1, 200000);
+        let part_1: u64 = range.invalid_ids().with_repeats(2).map(|id| id.id).sum();
+        let part_2: u64 = range.invalid_ids().map(|id| id.id).sum();
+        assert_eq!(part_1, part_1::sum_invalid_brute_force(&range));
+        assert_eq!(part_2, part_2::sum_all_invalid_in_range_single(&range));
+        // Ascending and without duplicates.
+        let ids = range.invalid_ids().map(|id| id.id).collect::<Vec<_>>();
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_invalid_ids_huge_range() {
+        let ids = Range::new(10u64.pow(15), u64::MAX)
+            .invalid_ids()
+            .take(2)
+            .map(|id| id.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![1000000010000000, 1000000110000001]);
+        let last = Range::new(u64::MAX - 10u64.pow(10), u64::MAX)
+            .invalid_ids()
+            .last();
+        assert_eq!(last.map(|id| id.id), Some(18446744071844674407));
+    }
+
+    #[test]
+    fn test_other_radixes_match_brute_force() {
+        for radix in [2, 3, 7, 16, 36] {
+            let range = Range::try_new(1, 100000, radix).unwrap();
+            assert_eq!(
+                sum_repeated_twice(&range).unwrap(),
+                part_1::sum_invalid_brute_force(&range) as u128,
+                "radix {radix}"
+            );
+            assert_eq!(
+                sum_repeated(&range).unwrap(),
+                part_2::sum_all_invalid_in_range_single(&range) as u128,
+                "radix {radix}"
+            );
+            let from_iter: u64 = range.invalid_ids().map(|id| id.id).sum();
+            assert_eq!(
+                from_iter as u128,
+                sum_repeated(&range).unwrap(),
+                "radix {radix}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_hex_ids() {
+        let range = Range::<u64>::parse_radix("aa-abab", 16).unwrap();
+        let ids = range
+            .invalid_ids()
+            .map(|id| id.to_string())
+            .take(3)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["aa = a x2", "bb = b x2", "cc = c x2"]);
+        // 0b101 is not a repeat in binary, but 5 is in decimal.
+        let range = Range::try_new(5u64, 5, 2).unwrap();
+        assert_eq!(sum_repeated(&range).unwrap(), 0);
+        let ids = Range::try_new(8, 15, 2).unwrap().invalid_ids();
+        let ids = ids.map(|id| id.to_string()).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["1010 = 10 x2", "1111 = 1 x4"]);
+    }
+
+    #[test]
+    fn test_zero() {
+        assert_eq!(sum_repeated(&Range::new(0, 1)).unwrap(), 0);
+        assert_eq!(sum_repeated_twice(&Range::new(0, 11)).unwrap(), 11);
+    }
+
+    #[test]
+    fn test_wide_range() {
+        // Every 20 digit number made of a 10 digit block twice, which is past the end of a u64.
+        let range = Range::<u128>::try_new(10u128.pow(19), 10u128.pow(20) - 1, 10).unwrap();
+        let (first, last) = (10u128.pow(9), 10u128.pow(10) - 1);
+        let expected = (10u128.pow(10) + 1) * (first + last) * (last - first + 1) / 2;
+        assert_eq!(sum_repeated_twice(&range).unwrap(), expected);
+        let id = 123456789012345_123456789012345;
+        let range = Range::<u128>::try_new(id - 1, id + 1, 10).unwrap();
+        assert_eq!(sum_repeated(&range).unwrap(), id);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let range = Range::<u128>::try_new(1, u128::MAX, 10).unwrap();
+        assert_eq!(sum_repeated(&range), Err(SumOverflow));
+        assert_eq!(sum_repeated_twice(&range), Err(SumOverflow));
+    }
+
+    #[test]
+    fn test_top_of_u64() {
+        let range = Range::new(u64::MAX - 10, u64::MAX);
+        assert_eq!(sum_repeated(&range).unwrap(), 0);
+    }
+}
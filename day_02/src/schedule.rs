@@ -0,0 +1,219 @@
+//! Spreads brute force work over threads, but only when there is enough of it to pay for the threads.
+//!
+//! Parallelising inside each range on its own, as `sum_all_invalid_in_db_multi` does, starts and joins a
+//! batch of tasks for every range, which costs more than it saves when the ranges are small. Instead the
+//! whole database is cut into chunks holding about the same number of IDs, whatever range they come from,
+//! and those chunks are handed out to the threads.
+//!
+//! The command line uses the closed form sums unless it's run with `--param method=scheduled`, which checks every
+//! ID through a scheduler with `--param threads=N` threads.
+
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder, prelude::*};
+
+use crate::{Range, ShopDatabase};
+
+/// Below this many IDs it's quicker to check them all on one thread. Each ID takes a few hundred
+/// nanoseconds, so this is tens of milliseconds of work. Comparing the "scheduled serial" and "scheduled parallel"
+/// benches across the synthetic inputs shows where it is on a given machine, and "scheduled adaptive" shows the
+/// threshold in use.
+pub const DEFAULT_PARALLEL_THRESHOLD: u64 = 50_000;
+
+/// Decides how to run a function over every range in a database, and runs it.
+#[derive(Debug)]
+pub struct Scheduler {
+    /// The threads to use, or rayon's global pool if `None`.
+    pool: Option<ThreadPool>,
+    /// The number of IDs at which work is spread over threads.
+    threshold: u64,
+    /// More chunks than threads evens things out when some IDs are slower to check than others.
+    chunks_per_thread: usize,
+}
+
+/// How a database will be worked through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Plan {
+    /// One range after another on the calling thread.
+    Serial,
+    /// Each chunk is a task of its own, made of whole or partial ranges.
+    Parallel(Vec<Vec<Range>>),
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self {
+            pool: None,
+            threshold: DEFAULT_PARALLEL_THRESHOLD,
+            chunks_per_thread: 4,
+        }
+    }
+}
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Runs on a pool of `threads` threads rather than the global one. 0 means one per core.
+    pub fn with_threads(mut self, threads: usize) -> Result<Self, ThreadPoolBuildError> {
+        self.pool = Some(ThreadPoolBuilder::new().num_threads(threads).build()?);
+        Ok(self)
+    }
+    /// Spreads the work over threads once there are at least `ids` IDs to check.
+    /// 0 always uses threads and `u64::MAX` never does.
+    pub fn parallel_threshold(mut self, ids: u64) -> Self {
+        self.threshold = ids;
+        self
+    }
+    pub fn threads(&self) -> usize {
+        self.pool
+            .as_ref()
+            .map_or_else(rayon::current_num_threads, ThreadPool::current_num_threads)
+    }
+    pub fn plan(&self, db: &ShopDatabase) -> Plan {
+        let work = estimate_work(db);
+        if self.threads() <= 1 || work < self.threshold as u128 {
+            Plan::Serial
+        } else {
+            let chunks = self.threads() * self.chunks_per_thread;
+            Plan::Parallel(balanced_chunks(&db.ranges, chunks))
+        }
+    }
+    /// Sums `f` over every range in the database, on however many threads the plan calls for.
+    /// `f` may be given part of a range rather than the whole thing, so it must add up across splits.
+    pub fn sum<F>(&self, db: &ShopDatabase, f: F) -> u64
+    where
+        F: Fn(&Range) -> u64 + Sync,
+    {
+        match self.plan(db) {
+            Plan::Serial => db.ranges.iter().map(&f).sum(),
+            Plan::Parallel(chunks) => {
+                let run = || {
+                    chunks
+                        .par_iter()
+                        .map(|chunk| chunk.iter().map(&f).sum::<u64>())
+                        .sum()
+                };
+                match &self.pool {
+                    Some(pool) => pool.install(run),
+                    None => run(),
+                }
+            }
+        }
+    }
+}
+
+/// The number of IDs that brute force would have to check.
+pub fn estimate_work(db: &ShopDatabase) -> u128 {
+    db.ranges.iter().map(range_len).sum()
+}
+
+/// Cuts the ranges into at most `chunks` chunks with as close to the same number of IDs as possible.
+/// Ranges are split wherever a chunk fills up, so one huge range is shared out as well as many small ones.
+pub fn balanced_chunks(ranges: &[Range], chunks: usize) -> Vec<Vec<Range>> {
+    let total: u128 = ranges.iter().map(range_len).sum();
+    if total == 0 {
+        return vec![];
+    }
+    let per_chunk = total.div_ceil(chunks.max(1) as u128);
+    let mut out = vec![];
+    let mut chunk = vec![];
+    let mut room = per_chunk;
+    for range in ranges.iter().filter(|range| range.start <= range.end) {
+        let mut start = range.start;
+        loop {
+            let left = (range.end - start) as u128 + 1;
+            if left < room {
+                chunk.push(Range { start, ..*range });
+                room -= left;
+                break;
+            }
+            // `room` is no more than `left` here, so this can't go past the end of the range.
+            let end = start + (room - 1) as u64;
+            chunk.push(Range {
+                start,
+                end,
+                ..*range
+            });
+            out.push(std::mem::take(&mut chunk));
+            room = per_chunk;
+            if end == range.end {
+                break;
+            }
+            start = end + 1;
+        }
+    }
+    if !chunk.is_empty() {
+        out.push(chunk);
+    }
+    out
+}
+
+fn range_len(range: &Range) -> u128 {
+    if range.start > range.end {
+        0
+    } else {
+        (range.end - range.start) as u128 + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part_2;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_balanced_chunks() {
+        let ranges = [
+            Range::new(1, 10),
+            Range::new(100, 101),
+            Range {
+                start: 5,
+                end: 4,
+                radix: 10,
+            },
+        ];
+        let chunks = balanced_chunks(&ranges, 3);
+        let expected = vec![
+            vec![Range::new(1, 4)],
+            vec![Range::new(5, 8)],
+            vec![Range::new(9, 10), Range::new(100, 101)],
+        ];
+        assert_eq!(chunks, expected);
+        assert!(balanced_chunks(&[], 4).is_empty());
+        // Every ID ends up in exactly one chunk even when the ranges go right up to the top.
+        let chunks = balanced_chunks(&[Range::new(u64::MAX - 9, u64::MAX)], 4);
+        let lens = chunks.iter().flatten().map(range_len).collect::<Vec<_>>();
+        assert_eq!(lens, vec![3, 3, 3, 1]);
+    }
+
+    #[test]
+    fn test_plan() {
+        let small = ShopDatabase::new(&[Range::new(11, 22)]);
+        let scheduler = Scheduler::new().with_threads(2).unwrap();
+        assert_eq!(scheduler.plan(&small), Plan::Serial);
+        let scheduler = scheduler.parallel_threshold(0);
+        assert!(matches!(scheduler.plan(&small), Plan::Parallel(_)));
+        let one_thread = Scheduler::new()
+            .with_threads(1)
+            .unwrap()
+            .parallel_threshold(0);
+        assert_eq!(one_thread.plan(&small), Plan::Serial);
+    }
+
+    #[test]
+    fn test_sum_matches_serial() {
+        let db =
+            ShopDatabase::from_str("11-22,95-115,998-1012,1188511880-1188511890,222220-222224")
+                .unwrap();
+        let expected = part_2::sum_all_invalid_in_db_single(&db);
+        for threshold in [0, u64::MAX] {
+            let scheduler = Scheduler::new()
+                .with_threads(3)
+                .unwrap()
+                .parallel_threshold(threshold);
+            assert_eq!(
+                scheduler.sum(&db, part_2::sum_all_invalid_in_range_single),
+                expected
+            );
+        }
+    }
+}
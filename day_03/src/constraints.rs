@@ -0,0 +1,657 @@
+//! Picking batteries under wiring rules, or for the smallest joltage rather than the largest.
+//!
+//! With no rules the greedy stack in `BatteryBank::select` is all that's needed, but rules like a minimum
+//! gap mean a good battery early on can rule out better ones later. So constrained selections go through
+//! a dynamic programme instead. It works up from the last battery picked to the first, scoring every way
+//! of carrying on so that the best one can be picked at each step. For single digit joltages the scores
+//! are ranks, so that comparing two choices is comparing two numbers rather than two strings of digits.
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::{BatteryBank, JoltageError, ParseError, Selection};
+
+/// Whether to look for the largest or the smallest joltage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Objective {
+    #[default]
+    Maximize,
+    Minimize,
+}
+impl Objective {
+    /// Whether joltage `a` makes a better digit than `b`.
+    pub(crate) fn is_better(self, a: u32, b: u32) -> bool {
+        match self {
+            Objective::Maximize => a > b,
+            Objective::Minimize => a < b,
+        }
+    }
+    /// A key that is bigger the better the joltage is.
+    pub(crate) fn key(self, joltage: u32) -> u32 {
+        match self {
+            Objective::Maximize => joltage,
+            Objective::Minimize => u32::MAX - joltage,
+        }
+    }
+    /// 1 or -1, to turn anything that is bigger the bigger it is into something bigger the better it is.
+    fn sign(self) -> i64 {
+        match self {
+            Objective::Maximize => 1,
+            Objective::Minimize => -1,
+        }
+    }
+}
+
+/// How the joltages of the picked batteries make the bank's output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Combine {
+    /// Read one after the other as the digits of a single number, as in the puzzle.
+    #[default]
+    Concatenate,
+    /// Added up.
+    Sum,
+}
+impl Display for Combine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Combine::Concatenate => write!(f, "concatenate"),
+            Combine::Sum => write!(f, "sum"),
+        }
+    }
+}
+impl FromStr for Combine {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "concatenate" => Ok(Combine::Concatenate),
+            "sum" => Ok(Combine::Sum),
+            _ => Err(ParseError::UnknownCombine(s.to_string())),
+        }
+    }
+}
+
+/// The rules for `BatteryBank::select_with`. The default is the plain largest joltage, as in the puzzle.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SelectOptions {
+    objective: Objective,
+    combine: Combine,
+    min_gap: usize,
+    /// `(length, max)`: at most `max` batteries out of each run of `length`.
+    segment_limit: Option<(usize, usize)>,
+    /// Pairs of positions that can't be picked one straight after the other, smallest position first.
+    forbidden_pairs: Vec<(usize, usize)>,
+}
+impl SelectOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Looks for the smallest joltage instead of the largest.
+    pub fn minimize(mut self) -> Self {
+        self.objective = Objective::Minimize;
+        self
+    }
+    /// Adds up the picked joltages instead of reading them as digits.
+    pub fn sum(mut self) -> Self {
+        self.combine = Combine::Sum;
+        self
+    }
+    /// Leaves at least `gap` batteries switched off between any two that are picked.
+    pub fn min_gap(mut self, gap: usize) -> Self {
+        self.min_gap = gap;
+        self
+    }
+    /// Splits the bank into segments of `length` batteries, the first starting at position 0,
+    /// and picks no more than `max` batteries from each.
+    pub fn max_per_segment(mut self, length: usize, max: usize) -> Self {
+        self.segment_limit = Some((length, max));
+        self
+    }
+    /// Stops the batteries at positions `a` and `b` being picked one straight after the other, that is
+    /// with no other picked battery between them. They can both still be picked with others in between.
+    /// Can be called any number of times.
+    pub fn forbid_pair(mut self, a: usize, b: usize) -> Self {
+        self.forbidden_pairs.push((a.min(b), a.max(b)));
+        self
+    }
+    pub fn objective(&self) -> Objective {
+        self.objective
+    }
+    pub fn combine(&self) -> Combine {
+        self.combine
+    }
+    fn is_unconstrained(&self) -> bool {
+        self.min_gap == 0 && self.segment_limit.is_none() && self.forbidden_pairs.is_empty()
+    }
+}
+
+impl BatteryBank {
+    /// Picks `k` batteries following the rules in `options`, with the same leftmost tie break as `select`.
+    /// Without a gap, segment limit or forbidden pair this is as fast as `select`, as long as the joltages
+    /// are summed or all have the same number of digits. Otherwise it takes O(k·n·m·log(n·m)) time and
+    /// O(k·n·m) memory for a bank of n batteries with at most m per segment, and a factor of the output's
+    /// length more when joltages of different lengths are concatenated. Each battery with a forbidden pair
+    /// adds another O(k·n·m) time, since its options are looked through one by one.
+    pub fn select_with(
+        &self,
+        k: usize,
+        options: &SelectOptions,
+    ) -> Result<Selection, JoltageError> {
+        let uniform = self.is_uniform_width();
+        if options.is_unconstrained() {
+            match options.combine {
+                Combine::Sum => return self.select_top(k, options.objective),
+                Combine::Concatenate if uniform => {
+                    return self.select_greedy(k, options.objective);
+                }
+                Combine::Concatenate => {}
+            }
+        }
+        if options.segment_limit.is_some_and(|(length, _)| length == 0) {
+            return Err(JoltageError::EmptySegment);
+        }
+        if self.joltages().len() < k {
+            return Err(JoltageError::BankTooShort {
+                needed: k,
+                available: self.joltages().len(),
+            });
+        }
+        if k == 0 {
+            return Ok(Selection::new(self, vec![]));
+        }
+        let solver = Solver::new(self.joltages(), options);
+        let objective = options.objective;
+        let positions = match options.combine {
+            Combine::Sum => solver.solve(k, &Summed(objective)),
+            Combine::Concatenate if uniform => solver.solve(k, &Ranked(objective)),
+            Combine::Concatenate => solver.solve(k, &Concatenated(objective)),
+        }
+        .ok_or(JoltageError::NoValidSelection(k))?;
+        Ok(Selection::new(self, positions))
+    }
+}
+
+/// How the solver scores picking a battery and then carrying on in the best way. Higher is better.
+trait Scoring {
+    type Score: Ord + Clone;
+    /// Whether a 0 picked before anything else is dropped from the output, as it is from a number.
+    /// If so, every battery also gets a score for when all the batteries picked before it are 0s, and
+    /// `finish` must leave scores alone so that the two kinds can be compared.
+    const DROPS_LEADING_ZEROS: bool = false;
+    /// The score of picking a battery of `joltage` followed by the picks scored `next`, if there are any.
+    fn score(&self, joltage: u32, next: Option<&Self::Score>) -> Self::Score;
+    /// The score of an output with nothing left in it once leading 0s are dropped.
+    fn zero(&self) -> Self::Score {
+        unreachable!("Only needed when leading zeros are dropped")
+    }
+    /// Tidies up the scores of every state once a step is done.
+    fn finish(&self, scores: Vec<Option<Self::Score>>) -> Vec<Option<Self::Score>> {
+        scores
+    }
+}
+
+/// For joltages that all have the same number of digits, so the best output is the one with the best
+/// first joltage, then the best second and so on. The scores of each step are ranked against each other
+/// before the next step so they stay small.
+struct Ranked(Objective);
+impl Scoring for Ranked {
+    type Score = u64;
+    fn score(&self, joltage: u32, next: Option<&u64>) -> u64 {
+        ((self.0.key(joltage) as u64) << 32) | next.copied().unwrap_or(0)
+    }
+    fn finish(&self, scores: Vec<Option<u64>>) -> Vec<Option<u64>> {
+        let mut sorted = scores.iter().flatten().copied().collect::<Vec<_>>();
+        sorted.sort_unstable();
+        sorted.dedup();
+        scores
+            .iter()
+            .map(|score| score.map(|score| sorted.binary_search(&score).unwrap() as u64))
+            .collect()
+    }
+}
+
+/// For joltages with different numbers of digits. Longer outputs are bigger numbers and outputs of the
+/// same length compare digit by digit, so the score is the length followed by every digit, both negated
+/// when minimizing. A 0 in the middle counts towards the length like any other digit, but 0s picked first
+/// are dropped like the leading zeros of any number.
+struct Concatenated(Objective);
+impl Scoring for Concatenated {
+    type Score = (i64, Vec<i8>);
+    const DROPS_LEADING_ZEROS: bool = true;
+    fn zero(&self) -> Self::Score {
+        (0, vec![])
+    }
+    fn score(&self, joltage: u32, next: Option<&Self::Score>) -> Self::Score {
+        let sign = self.0.sign();
+        let digits = joltage.to_string();
+        let (length, rest) = next.map_or((0, &[][..]), |(length, rest)| (*length, &rest[..]));
+        let keys = digits
+            .bytes()
+            .map(|digit| (digit - b'0') as i8 * sign as i8)
+            .chain(rest.iter().copied())
+            .collect();
+        (length + digits.len() as i64 * sign, keys)
+    }
+}
+
+/// For adding the joltages up.
+struct Summed(Objective);
+impl Scoring for Summed {
+    type Score = i64;
+    fn score(&self, joltage: u32, next: Option<&i64>) -> i64 {
+        joltage as i64 * self.0.sign() + next.copied().unwrap_or(0)
+    }
+}
+
+/// A state is a battery being picked along with how many have been picked in its segment so far,
+/// counting itself. Without a segment limit the count doesn't matter and is always 1.
+struct Solver<'a> {
+    joltages: &'a [u32],
+    gap: usize,
+    /// Whether there is a segment limit that can actually be reached.
+    limited: bool,
+    segment_length: usize,
+    /// The most batteries per segment, and so the number of counts each battery has a state for.
+    per_segment: usize,
+    /// For each position, the later positions that can't be picked straight after it.
+    forbidden: Vec<Vec<usize>>,
+}
+
+impl<'a> Solver<'a> {
+    fn new(joltages: &'a [u32], options: &SelectOptions) -> Self {
+        let (limited, segment_length, per_segment) = match options.segment_limit {
+            Some((length, max)) if max < length => (true, length, max),
+            // One segment covering everything, with room for every battery.
+            _ => (false, joltages.len().max(1), 1),
+        };
+        let mut forbidden = vec![vec![]; joltages.len()];
+        for &(a, b) in &options.forbidden_pairs {
+            if a != b && b < joltages.len() {
+                forbidden[a].push(b);
+            }
+        }
+        Self {
+            joltages,
+            gap: options.min_gap,
+            limited,
+            segment_length,
+            per_segment,
+            forbidden,
+        }
+    }
+    fn state(&self, position: usize, count: usize) -> usize {
+        position * self.per_segment + count - 1
+    }
+    fn joltage(&self, state: usize) -> u32 {
+        self.joltages[state / self.per_segment]
+    }
+    fn segment_end(&self, position: usize) -> usize {
+        ((position / self.segment_length + 1) * self.segment_length).min(self.joltages.len())
+    }
+
+    /// The positions of the best `k` batteries, or `None` if the rules can't be kept.
+    fn solve<S: Scoring>(&self, k: usize, scoring: &S) -> Option<Vec<usize>> {
+        let n = self.joltages.len();
+        if self.per_segment == 0 {
+            return None;
+        }
+        let states = n * self.per_segment;
+        // `scores[s]` scores the best way of picking the last `r` batteries starting with state `s`.
+        // `None` means it can't be done.
+        let mut scores: Vec<Option<S::Score>> = vec![None; states];
+        // The same, but for when every battery picked before `s` is a 0, so a 0 at `s` is dropped too.
+        // Only kept when the scoring drops leading zeros.
+        let mut leading: Vec<Option<S::Score>> = vec![];
+        // `next[r][s]` is the state picked after `s` when there are `r + 2` batteries left to pick, and
+        // `next_leading[r][s]` the same when every battery up to and including `s` is a 0.
+        let mut next: Vec<Vec<Option<usize>>> = Vec::with_capacity(k.saturating_sub(1));
+        let mut next_leading: Vec<Vec<Option<usize>>> = vec![];
+        for r in 1..=k {
+            let step: Vec<_> = if r == 1 {
+                (0..states)
+                    .map(|s| Some(scoring.score(self.joltage(s), None)))
+                    .collect()
+            } else {
+                let chosen = self.best_continuations(&scores);
+                let step = chosen
+                    .iter()
+                    .enumerate()
+                    .map(|(s, best)| {
+                        best.map(|best| scoring.score(self.joltage(s), scores[best].as_ref()))
+                    })
+                    .collect();
+                next.push(chosen);
+                step
+            };
+            if S::DROPS_LEADING_ZEROS {
+                // A 0 after nothing but 0s adds nothing, so it's worth whatever the best way of carrying
+                // on from it is. Anything else starts the number, so it scores as usual.
+                let chosen = (r > 1).then(|| self.best_continuations(&leading));
+                leading = (0..states)
+                    .map(|s| match (self.joltage(s), &chosen) {
+                        (0, None) => Some(scoring.zero()),
+                        (0, Some(chosen)) => chosen[s].and_then(|best| leading[best].clone()),
+                        _ => step[s].clone(),
+                    })
+                    .collect();
+                next_leading.extend(chosen);
+            }
+            scores = scoring.finish(step);
+        }
+        // Nothing has been picked before the first battery, so it counts as following only 0s.
+        let first_scores = if S::DROPS_LEADING_ZEROS {
+            &leading
+        } else {
+            &scores
+        };
+        // Only a count of 1 makes sense for the first battery picked.
+        let first = (0..n)
+            .map(|position| self.state(position, 1))
+            .filter(|&s| first_scores[s].is_some())
+            // Ties go to the earliest position, as `max_by` keeps the last of equal elements.
+            .rev()
+            .max_by(|&a, &b| first_scores[a].cmp(&first_scores[b]))?;
+        let mut positions = vec![first / self.per_segment];
+        let mut state = first;
+        let mut only_zeros = S::DROPS_LEADING_ZEROS;
+        for r in (0..next.len()).rev() {
+            only_zeros &= self.joltage(state) == 0;
+            let chosen = if only_zeros {
+                &next_leading[r]
+            } else {
+                &next[r]
+            };
+            state = chosen[state].expect("Scored states always have a way to carry on");
+            positions.push(state / self.per_segment);
+        }
+        Some(positions)
+    }
+
+    /// For every state, the best scored state that can be picked straight after it. Ties go to the leftmost.
+    fn best_continuations<T: Ord>(&self, scores: &[Option<T>]) -> Vec<Option<usize>> {
+        let n = self.joltages.len();
+        let states = scores.len();
+        // Keeps `a` unless `b` is strictly better, so scanning right to left leaves the leftmost of equals.
+        let better = |a: Option<usize>, b: Option<usize>| match (a, b) {
+            (Some(sa), Some(sb)) if scores[sa] >= scores[sb] => a,
+            (_, None) => a,
+            _ => b,
+        };
+        let scored = |s: usize| scores[s].is_some().then_some(s);
+        // Picking a battery in a later segment starts its count at 1, from anywhere at or after a position.
+        let mut later: Vec<Option<usize>> = vec![None; n + 1];
+        for q in (0..n).rev() {
+            later[q] = better(scored(self.state(q, 1)), later[q + 1]);
+        }
+        // Picking another battery in the same segment adds to the count, so this is kept per count and
+        // starts afresh at each segment boundary.
+        let mut within: Vec<Option<usize>> = vec![None; states];
+        if self.limited {
+            for count in 1..=self.per_segment {
+                for q in (0..n).rev() {
+                    let s = self.state(q, count);
+                    let after = if q + 1 < self.segment_end(q) {
+                        within[self.state(q + 1, count)]
+                    } else {
+                        None
+                    };
+                    within[s] = better(scored(s), after);
+                }
+            }
+        }
+
+        let mut chosen = vec![None; states];
+        for p in 0..n {
+            let from = p.saturating_add(self.gap).saturating_add(1);
+            for count in 1..=self.per_segment {
+                let s = self.state(p, count);
+                chosen[s] = if !self.forbidden[p].is_empty() {
+                    // The running bests above might be forbidden, so look through every option instead.
+                    // Without a segment limit every option starts a count of its own.
+                    let end = if self.limited { self.segment_end(p) } else { 0 };
+                    (from.min(n)..n)
+                        .rev()
+                        .filter(|q| !self.forbidden[p].contains(q))
+                        .filter_map(|q| match q < end {
+                            true if count < self.per_segment => Some(self.state(q, count + 1)),
+                            true => None,
+                            false => Some(self.state(q, 1)),
+                        })
+                        .fold(None, |best, s| better(scored(s), best))
+                } else if self.limited {
+                    let end = self.segment_end(p);
+                    let same = (from < end && count < self.per_segment)
+                        .then(|| within[self.state(from, count + 1)])
+                        .flatten();
+                    better(same, later[from.max(end).min(n)])
+                } else {
+                    later[from.min(n)]
+                };
+            }
+        }
+        chosen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tries every set of `k` positions, keeping the best output and the leftmost positions among equals.
+    fn brute_force(bank: &BatteryBank, k: usize, options: &SelectOptions) -> Option<Vec<usize>> {
+        let n = bank.joltages().len();
+        let mut best: Option<(i128, Vec<usize>)> = None;
+        for mask in 0u32..(1 << n) {
+            if mask.count_ones() as usize != k {
+                continue;
+            }
+            let positions = (0..n).filter(|i| mask & (1 << i) != 0).collect::<Vec<_>>();
+            let gaps_ok = positions.windows(2).all(|w| {
+                w[1] - w[0] > options.min_gap && !options.forbidden_pairs.contains(&(w[0], w[1]))
+            });
+            let segments_ok = options.segment_limit.is_none_or(|(length, max)| {
+                (0..n.div_ceil(length))
+                    .all(|seg| positions.iter().filter(|&&p| p / length == seg).count() <= max)
+            });
+            if !gaps_ok || !segments_ok {
+                continue;
+            }
+            let joltages = positions.iter().map(|&p| bank.joltages()[p]);
+            let output: i128 = match options.combine {
+                Combine::Concatenate => joltages
+                    .map(|joltage| joltage.to_string())
+                    .collect::<String>()
+                    .parse()
+                    .unwrap_or(0),
+                Combine::Sum => joltages.map(|joltage| joltage as i128).sum(),
+            };
+            let score = output * options.objective.sign() as i128;
+            let is_better = match &best {
+                None => true,
+                Some((best_score, best_positions)) => {
+                    score > *best_score || (score == *best_score && positions < *best_positions)
+                }
+            };
+            if is_better {
+                best = Some((score, positions));
+            }
+        }
+        best.map(|(_, positions)| positions)
+    }
+
+    /// Random banks of up to 10 batteries, each with a joltage drawn from `joltages`.
+    fn banks(joltages: std::ops::Range<u32>) -> Vec<BatteryBank> {
+        let mut state = 11u64;
+        let mut next = || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            (state >> 33) as u32
+        };
+        (0..60)
+            .map(|_| {
+                let len = next() as usize % 10 + 1;
+                BatteryBank(
+                    (0..len)
+                        .map(|_| joltages.start + next() % joltages.len() as u32)
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// Forbids a few pairs, some of them next to each other and some further apart.
+    fn forbidding(options: SelectOptions) -> SelectOptions {
+        options
+            .forbid_pair(0, 1)
+            .forbid_pair(4, 2)
+            .forbid_pair(1, 5)
+            .forbid_pair(3, 4)
+            .forbid_pair(6, 9)
+    }
+
+    fn check_against_brute_force(banks: &[BatteryBank], options: &[SelectOptions]) {
+        for bank in banks {
+            for options in options {
+                for k in 0..=bank.joltages().len() {
+                    let expected = brute_force(bank, k, options);
+                    let actual = bank.select_with(k, options);
+                    match expected {
+                        Some(positions) => {
+                            assert_eq!(
+                                actual.unwrap().positions,
+                                positions,
+                                "{bank:?} {k} {options:?}"
+                            )
+                        }
+                        None => assert_eq!(
+                            actual,
+                            Err(JoltageError::NoValidSelection(k)),
+                            "{bank:?} {k} {options:?}"
+                        ),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let options = [
+            SelectOptions::new().min_gap(1),
+            SelectOptions::new().min_gap(2).minimize(),
+            SelectOptions::new().max_per_segment(3, 1),
+            SelectOptions::new().max_per_segment(4, 2).min_gap(1),
+            SelectOptions::new().max_per_segment(2, 1).minimize(),
+            forbidding(SelectOptions::new()),
+            forbidding(SelectOptions::new().min_gap(1).minimize()),
+            forbidding(SelectOptions::new().max_per_segment(3, 2)),
+        ];
+        check_against_brute_force(&banks(6..10), &options);
+    }
+
+    #[test]
+    fn test_multi_digit_and_sum_match_brute_force() {
+        let options = [
+            SelectOptions::new(),
+            SelectOptions::new().minimize(),
+            SelectOptions::new().sum(),
+            SelectOptions::new().sum().minimize(),
+            SelectOptions::new().min_gap(1),
+            SelectOptions::new().min_gap(1).sum(),
+            SelectOptions::new().max_per_segment(3, 1).minimize(),
+            SelectOptions::new().max_per_segment(4, 2).sum().minimize(),
+            forbidding(SelectOptions::new().minimize()),
+            forbidding(SelectOptions::new().sum()),
+        ];
+        check_against_brute_force(&banks(0..150), &options);
+        // Plenty of 0s, which are dropped from the front of the output.
+        check_against_brute_force(&banks(0..12), &options);
+        // Single digits summed go through the same paths as any other joltages.
+        check_against_brute_force(&banks(6..10), &options[2..4]);
+    }
+
+    #[test]
+    fn test_greedy_fast_path() {
+        let minimize = SelectOptions::new().minimize();
+        for bank in banks(6..10) {
+            for k in 0..=bank.joltages().len() {
+                assert_eq!(bank.select_with(k, &SelectOptions::new()), bank.select(k));
+                let positions = brute_force(&bank, k, &minimize);
+                assert_eq!(
+                    bank.select_with(k, &minimize).unwrap().positions,
+                    positions.unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_examples() {
+        let bank = "818181911112111".parse::<BatteryBank>().unwrap();
+        // There are 4 batteries between the 9 and the 2, so a gap of 4 still allows them but 5 doesn't.
+        let selection = bank
+            .select_with(2, &SelectOptions::new().min_gap(4))
+            .unwrap();
+        assert_eq!(selection.digits, vec![9, 2]);
+        let selection = bank
+            .select_with(2, &SelectOptions::new().min_gap(5))
+            .unwrap();
+        assert_eq!(selection.digits, vec![9, 1]);
+        let selection = bank
+            .select_with(3, &SelectOptions::new().minimize())
+            .unwrap();
+        assert_eq!(selection.digits, vec![1, 1, 1]);
+        assert_eq!(
+            bank.select_with(8, &SelectOptions::new().min_gap(1)),
+            Ok(Selection::new(&bank, vec![0, 2, 4, 6, 8, 10, 12, 14]))
+        );
+        assert_eq!(
+            bank.select_with(9, &SelectOptions::new().min_gap(1)),
+            Err(JoltageError::NoValidSelection(9))
+        );
+        assert_eq!(
+            bank.select_with(2, &SelectOptions::new().max_per_segment(0, 1)),
+            Err(JoltageError::EmptySegment)
+        );
+    }
+
+    #[test]
+    fn test_leading_zeros_are_dropped() {
+        let bank = "5,0,12".parse::<BatteryBank>().unwrap();
+        // 012 is just 12, which is smaller than 50.
+        let selection = bank
+            .select_with(2, &SelectOptions::new().minimize())
+            .unwrap();
+        assert_eq!(selection.positions, vec![1, 2]);
+        let bank = "0,0,7,10".parse::<BatteryBank>().unwrap();
+        let selection = bank
+            .select_with(3, &SelectOptions::new().minimize())
+            .unwrap();
+        assert_eq!(selection.positions, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_forbidden_pairs() {
+        let bank = "9819".parse::<BatteryBank>().unwrap();
+        let options = SelectOptions::new().forbid_pair(3, 0);
+        // The two 9s can't go straight after each other, but they can with another battery between them.
+        assert_eq!(bank.select_with(2, &options).unwrap().digits, vec![9, 8]);
+        assert_eq!(bank.select_with(3, &options).unwrap().digits, vec![9, 8, 9]);
+        let bank = "99".parse::<BatteryBank>().unwrap();
+        assert_eq!(
+            bank.select_with(2, &SelectOptions::new().forbid_pair(0, 1)),
+            Err(JoltageError::NoValidSelection(2))
+        );
+        // Pairs that aren't in the bank don't rule anything out.
+        let options = SelectOptions::new().forbid_pair(1, 7).forbid_pair(1, 1);
+        assert_eq!(bank.select_with(2, &options).unwrap().digits, vec![9, 9]);
+    }
+
+    #[test]
+    fn test_huge_gap() {
+        let bank = "8191".parse::<BatteryBank>().unwrap();
+        let options = SelectOptions::new().min_gap(usize::MAX);
+        assert_eq!(bank.select_with(1, &options).unwrap().digits, vec![9]);
+        assert_eq!(
+            bank.select_with(2, &options),
+            Err(JoltageError::NoValidSelection(2))
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use std::{fmt::Display, iter::Sum, ops::AddAssign};
+
+/// A joltage, or a total of joltages, with as many digits as it needs.
+/// Picking thousands of batteries makes numbers far too big for any integer type.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Joltage {
+    /// Least significant digit first, with no leading zeros. Zero has no digits at all.
+    digits: Vec<u8>,
+}
+impl Joltage {
+    /// Builds a joltage from its digits, most significant first.
+    pub fn from_digits(digits: &[u8]) -> Self {
+        let mut digits = digits.iter().rev().copied().collect::<Vec<_>>();
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        Self { digits }
+    }
+    /// The digits, most significant first.
+    pub fn digits(&self) -> Vec<u8> {
+        if self.digits.is_empty() {
+            return vec![0];
+        }
+        self.digits.iter().rev().copied().collect()
+    }
+    /// The joltage as a `u64`, if it is small enough.
+    pub fn to_u64(&self) -> Option<u64> {
+        self.digits.iter().rev().try_fold(0u64, |n, &digit| {
+            n.checked_mul(10)?.checked_add(digit as u64)
+        })
+    }
+}
+impl From<u64> for Joltage {
+    fn from(mut n: u64) -> Self {
+        let mut digits = vec![];
+        while n > 0 {
+            digits.push((n % 10) as u8);
+            n /= 10;
+        }
+        Self { digits }
+    }
+}
+impl AddAssign<&Joltage> for Joltage {
+    fn add_assign(&mut self, other: &Joltage) {
+        if other.digits.len() > self.digits.len() {
+            self.digits.resize(other.digits.len(), 0);
+        }
+        let mut carry = 0;
+        for (i, digit) in self.digits.iter_mut().enumerate() {
+            let sum = *digit + other.digits.get(i).copied().unwrap_or(0) + carry;
+            *digit = sum % 10;
+            carry = sum / 10;
+            if carry == 0 && i >= other.digits.len() {
+                break;
+            }
+        }
+        if carry > 0 {
+            self.digits.push(carry);
+        }
+    }
+}
+impl Sum for Joltage {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Joltage::default(), |mut total, joltage| {
+            total += &joltage;
+            total
+        })
+    }
+}
+impl Display for Joltage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = self
+            .digits()
+            .iter()
+            .map(|&digit| char::from(b'0' + digit))
+            .collect::<String>();
+        write!(f, "{digits}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digits_round_trip() {
+        let joltage = Joltage::from_digits(&[0, 0, 1, 2, 3]);
+        assert_eq!(joltage.digits(), vec![1, 2, 3]);
+        assert_eq!(joltage.to_string(), "123");
+        assert_eq!(joltage, Joltage::from(123));
+        assert_eq!(Joltage::from_digits(&[0]).to_string(), "0");
+        assert_eq!(Joltage::from(0).to_u64(), Some(0));
+    }
+
+    #[test]
+    fn test_add() {
+        let mut total = Joltage::from(999);
+        total += &Joltage::from(1);
+        assert_eq!(total, Joltage::from(1000));
+        total += &Joltage::from(123456);
+        assert_eq!(total.to_u64(), Some(124456));
+        let sum: Joltage = [u64::MAX, u64::MAX].into_iter().map(Joltage::from).sum();
+        assert_eq!(sum.to_string(), "36893488147419103230");
+        assert_eq!(sum.to_u64(), None);
+    }
+}
@@ -1,23 +1,179 @@
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 
 use anyhow::{Context, Result};
+use common::explain::{self, Event};
+pub use common::input::Normalization;
+pub use common::params::Params;
+use common::params::{Param, ParamSpec};
 use thiserror::Error;
 
+mod constraints;
+mod joltage;
 mod part_1;
 mod part_2;
+mod selection;
 
-pub fn run_part_1(input: &str) -> Result<u64> {
-    part_1::run(input).context("Failed to run part 1")
+pub use constraints::{Combine, Objective, SelectOptions};
+pub use joltage::Joltage;
+pub use selection::{Highlight, Selection};
+
+/// How the input is cleaned up before it is parsed.
+pub const NORMALIZATION: Normalization = Normalization::Full;
+
+/// The number of batteries part 1 turns on in each bank.
+pub const PART_1_LENGTH: usize = 2;
+/// The required length of the joltage output in digits for part 2.
+pub const LENGTH: Param<usize> = Param::new(
+    "length",
+    12,
+    "Number of batteries to turn on in each bank for part 2",
+);
+pub const FORMAT: Param<BankFormat> = Param::new(
+    "format",
+    BankFormat::Auto,
+    "digits for one single digit joltage per char, delimited for joltages split by commas or spaces, or auto to tell from each line",
+);
+pub const COMBINE: Param<Combine> = Param::new(
+    "combine",
+    Combine::Concatenate,
+    "concatenate to read the picked joltages as the digits of one number, or sum to add them up",
+);
+pub const PARAMS: &[&dyn ParamSpec] = &[&LENGTH, &FORMAT, &COMBINE];
+
+pub fn run_part_1(input: &str, params: &Params) -> Result<Joltage> {
+    part_1::run(input, params.get(&FORMAT), &select_options(params)).context("Failed to run part 1")
+}
+
+pub fn run_part_2(input: &str, params: &Params) -> Result<Joltage> {
+    part_2::run(
+        input,
+        params.get(&LENGTH),
+        params.get(&FORMAT),
+        &select_options(params),
+    )
+    .context("Failed to run part 2")
+}
+
+/// Runs part 1 one bank at a time, for inputs too big to load all at once.
+pub fn stream_part_1<'a>(lines: impl Iterator<Item = &'a str>, params: &Params) -> Result<Joltage> {
+    part_1::run_streaming(lines, params.get(&FORMAT), &select_options(params))
+        .context("Failed to run part 1")
 }
 
-pub fn run_part_2(input: &str) -> Result<u64> {
-    part_2::run(input).context("Failed to run part 2")
+/// Runs part 2 one bank at a time, for inputs too big to load all at once.
+pub fn stream_part_2<'a>(lines: impl Iterator<Item = &'a str>, params: &Params) -> Result<Joltage> {
+    part_2::run_streaming(
+        lines,
+        params.get(&LENGTH),
+        params.get(&FORMAT),
+        &select_options(params),
+    )
+    .context("Failed to run part 2")
 }
 
-#[derive(Debug, Error)]
+/// The selection rules that the parameters ask for.
+pub fn select_options(params: &Params) -> SelectOptions {
+    match params.get(&COMBINE) {
+        Combine::Concatenate => SelectOptions::new(),
+        Combine::Sum => SelectOptions::new().sum(),
+    }
+}
+
+/// Positions count chars from the start of the line, starting at 0.
+#[derive(Debug, Error, PartialEq, Eq)]
 pub enum ParseError {
-    #[error("Could not convert char {0} to joltage")]
-    InvalidChar(char),
+    #[error("Could not convert char {found} at position {position} to joltage")]
+    InvalidChar { found: char, position: usize },
+    #[error("Expected a joltage at position {position}")]
+    MissingJoltage { position: usize },
+    #[error(
+        "The joltage starting at position {position} is bigger than {}",
+        u32::MAX
+    )]
+    JoltageTooLarge { position: usize },
+    #[error("Unknown bank format {0}, expected auto, digits or delimited")]
+    UnknownFormat(String),
+    #[error("Unknown combine mode {0}, expected concatenate or sum")]
+    UnknownCombine(String),
+}
+
+/// How the joltages of a bank are written in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BankFormat {
+    /// `Delimited` if the line has a comma in it or whitespace between two runs of digits, `Digits` otherwise.
+    /// Any other whitespace is left for the digits to report as an invalid char.
+    Auto,
+    /// One char per battery, each a joltage from 0 to 9, as in the puzzle.
+    Digits,
+    /// Joltages of any size split by commas, spaces or both, e.g. `12,7,130,4`.
+    Delimited,
+}
+impl BankFormat {
+    /// The format of `line`, working it out from the line itself for `Auto`.
+    pub fn resolve(self, line: &str) -> BankFormat {
+        match self {
+            BankFormat::Auto if line.contains(',') || splits_digit_runs(line) => {
+                BankFormat::Delimited
+            }
+            BankFormat::Auto => BankFormat::Digits,
+            format => format,
+        }
+    }
+}
+/// Whether some whitespace in `line` has digits straight either side of it, as in `12 7`.
+fn splits_digit_runs(line: &str) -> bool {
+    // The last char before the current run of whitespace, and whether there has been any whitespace since.
+    let mut last = None;
+    let mut gap = false;
+    for c in line.chars() {
+        if c.is_whitespace() {
+            gap = true;
+            continue;
+        }
+        if gap && c.is_ascii_digit() && last.is_some_and(|last: char| last.is_ascii_digit()) {
+            return true;
+        }
+        last = Some(c);
+        gap = false;
+    }
+    false
+}
+impl Display for BankFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BankFormat::Auto => write!(f, "auto"),
+            BankFormat::Digits => write!(f, "digits"),
+            BankFormat::Delimited => write!(f, "delimited"),
+        }
+    }
+}
+impl FromStr for BankFormat {
+    type Err = ParseError;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(BankFormat::Auto),
+            "digits" => Ok(BankFormat::Digits),
+            "delimited" => Ok(BankFormat::Delimited),
+            _ => Err(ParseError::UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+/// The most digits a joltage can have and still fit in a `u64`.
+pub const MAX_JOLTAGE_DIGITS: usize = 19;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum JoltageError {
+    #[error("Expected a bank with at least {needed} batteries but it only has {available}")]
+    BankTooShort { needed: usize, available: usize },
+    #[error(
+        "A joltage of {0} digits does not fit in a u64, the most is {MAX_JOLTAGE_DIGITS}. Use max_joltage_digits instead"
+    )]
+    TooManyDigits(usize),
+    #[error("There is no way to turn on {0} batteries that follows the constraints")]
+    NoValidSelection(usize),
+    #[error("Segments must be at least one battery long")]
+    EmptySegment,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -25,15 +181,19 @@ pub struct AllBatteries(pub Vec<BatteryBank>);
 impl FromStr for AllBatteries {
     type Err = ParseError;
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        AllBatteries::parse(s, BankFormat::Auto)
+    }
+}
+impl AllBatteries {
+    /// Reads one bank per line, each in the given format.
+    pub fn parse(s: &str, format: BankFormat) -> Result<Self, ParseError> {
         let all_banks = s
             .trim()
             .lines()
-            .map(|line| BatteryBank::from_str(line))
+            .map(|line| BatteryBank::parse(line, format))
             .collect::<Result<Vec<_>, ParseError>>()?;
         Ok(AllBatteries(all_banks))
     }
-}
-impl AllBatteries {
     pub fn iter(&self) -> impl Iterator<Item = &BatteryBank> {
         self.0.iter()
     }
@@ -44,17 +204,215 @@ pub struct BatteryBank(pub Vec<u32>);
 impl FromStr for BatteryBank {
     type Err = ParseError;
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let all_joltages = s
-            .chars()
-            .map(|c| c.to_digit(10).ok_or(ParseError::InvalidChar(c)))
-            .collect::<Result<Vec<_>, ParseError>>()?;
-        Ok(BatteryBank(all_joltages))
+        BatteryBank::parse(s, BankFormat::Auto)
     }
 }
 impl BatteryBank {
+    pub fn parse(s: &str, format: BankFormat) -> Result<Self, ParseError> {
+        let all_joltages = match format.resolve(s) {
+            BankFormat::Delimited => parse_delimited(s)?,
+            _ => s
+                .chars()
+                .enumerate()
+                .map(|(position, c)| {
+                    c.to_digit(10)
+                        .ok_or(ParseError::InvalidChar { found: c, position })
+                })
+                .collect::<Result<Vec<_>, ParseError>>()?,
+        };
+        Ok(BatteryBank(all_joltages))
+    }
     pub fn joltages(&self) -> &[u32] {
         &self.0
     }
+    /// The largest joltage that can be made by turning on `k` batteries, reading their joltages from left
+    /// to right as the digits of a single number.
+    pub fn max_joltage(&self, k: usize) -> Result<u64, JoltageError> {
+        let joltage = Joltage::from_digits(&self.max_joltage_digits(k)?);
+        // Leading zeros don't count, but joltages with more than one digit can make too long a number from
+        // fewer batteries.
+        joltage
+            .to_u64()
+            .ok_or_else(|| JoltageError::TooManyDigits(joltage.digits().len()))
+    }
+    /// The digits of the largest joltage from `k` batteries, for when there are too many for a `u64`.
+    pub fn max_joltage_digits(&self, k: usize) -> Result<Vec<u8>, JoltageError> {
+        self.select(k).map(|selection| selection.digits)
+    }
+    /// The joltage that `k` batteries picked with `options` put out, combined the way `options` says.
+    pub fn output_joltage(
+        &self,
+        k: usize,
+        options: &SelectOptions,
+    ) -> Result<Joltage, JoltageError> {
+        self.select_with(k, options)
+            .map(|selection| selection.output(options.combine()))
+    }
+    /// Picks the `k` batteries that make the largest joltage, returning where they are as well as their digits.
+    ///
+    /// Ties are broken by taking the leftmost batteries: each digit comes from the earliest position that
+    /// still gives the largest joltage, so picking 2 from `999` turns on the first two.
+    ///
+    /// When every joltage has the same number of digits this works in a single pass by keeping the best
+    /// digits so far on a stack. Each new joltage knocks off the smaller ones on top of the stack, as long
+    /// as there are still batteries to spare to make up `k`, so a joltage is only ever pushed and popped once.
+    pub fn select(&self, k: usize) -> Result<Selection, JoltageError> {
+        self.select_with(k, &SelectOptions::new())
+    }
+    /// The stack version of `select`, for either objective. Without any constraints, and with joltages
+    /// that are all as long as each other, the best joltage only depends on their order.
+    pub(crate) fn select_greedy(
+        &self,
+        k: usize,
+        objective: Objective,
+    ) -> Result<Selection, JoltageError> {
+        let joltages = self.joltages();
+        self.check_length(k)?;
+        let mut to_drop = joltages.len() - k;
+        let mut stack: Vec<usize> = Vec::with_capacity(joltages.len());
+        for (position, &joltage) in joltages.iter().enumerate() {
+            // Only strictly worse joltages are dropped, so of two equal ones the leftmost is kept.
+            while to_drop > 0
+                && stack
+                    .last()
+                    .is_some_and(|&top| objective.is_better(joltage, joltages[top]))
+            {
+                stack.pop();
+                to_drop -= 1;
+            }
+            stack.push(position);
+        }
+        stack.truncate(k);
+        for &position in &stack {
+            explain::record(|| {
+                Event::new("battery_digit")
+                    .with("position", position)
+                    .with("joltage", joltages[position])
+            });
+        }
+        Ok(Selection::new(self, stack))
+    }
+    /// The `k` best joltages wherever they are, for when they are added up rather than read as digits.
+    /// Of equal joltages the leftmost are picked.
+    pub(crate) fn select_top(
+        &self,
+        k: usize,
+        objective: Objective,
+    ) -> Result<Selection, JoltageError> {
+        let joltages = self.joltages();
+        self.check_length(k)?;
+        let mut order = (0..joltages.len()).collect::<Vec<_>>();
+        // The sort is stable, so equal joltages stay in the order they appear.
+        order.sort_by_key(|&position| std::cmp::Reverse(objective.key(joltages[position])));
+        let mut positions = order[..k].to_vec();
+        positions.sort_unstable();
+        for &position in &positions {
+            explain::record(|| {
+                Event::new("battery_digit")
+                    .with("position", position)
+                    .with("joltage", joltages[position])
+            });
+        }
+        Ok(Selection::new(self, positions))
+    }
+    /// Whether every joltage has the same number of digits, as they do in the puzzle.
+    pub(crate) fn is_uniform_width(&self) -> bool {
+        self.0.windows(2).all(|w| width(w[0]) == width(w[1]))
+    }
+    /// The original O(n·k) version, which rescans the batteries for every digit.
+    /// Kept to check `select` against, including which of several equal batteries it picks.
+    /// Only right for single digit joltages, since it stops looking as soon as it sees a 9.
+    #[cfg(test)]
+    fn select_sliding(&self, k: usize) -> Result<Selection, JoltageError> {
+        let joltages = self.joltages();
+        self.check_length(k)?;
+        let mut positions = Vec::with_capacity(k);
+        let mut current_index = 0;
+        for i in 0..k {
+            // The highest digit is picked first, as long as it leaves enough batteries after it for the rest.
+            // Each digit after that can't be left of the one before, so the window slides right as we go.
+            let slice = &joltages[current_index..(joltages.len() - k + i + 1)];
+            let index = find_highest_index_from_left(slice);
+            positions.push(current_index + index);
+            current_index += index + 1;
+        }
+        Ok(Selection::new(self, positions))
+    }
+    fn check_length(&self, k: usize) -> Result<(), JoltageError> {
+        if self.0.len() < k {
+            return Err(JoltageError::BankTooShort {
+                needed: k,
+                available: self.0.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Reads joltages split by commas and/or spaces. A run of spaces counts as one split, but two commas
+/// with nothing between them, or a comma at either end, is a missing joltage.
+fn parse_delimited(s: &str) -> Result<Vec<u32>, ParseError> {
+    let mut joltages = vec![];
+    // The joltage being read so far, along with where it started.
+    let mut current: Option<(u32, usize)> = None;
+    // Whether there has been a comma since the last joltage.
+    let mut comma = false;
+    for (position, c) in s.chars().enumerate() {
+        if let Some(digit) = c.to_digit(10) {
+            let (joltage, start) = current.unwrap_or((0, position));
+            let joltage = joltage
+                .checked_mul(10)
+                .and_then(|joltage| joltage.checked_add(digit))
+                .ok_or(ParseError::JoltageTooLarge { position: start })?;
+            current = Some((joltage, start));
+        } else if c == ',' || c.is_whitespace() {
+            if let Some((joltage, _)) = current.take() {
+                joltages.push(joltage);
+                comma = false;
+            }
+            if c == ',' {
+                if comma || joltages.is_empty() {
+                    return Err(ParseError::MissingJoltage { position });
+                }
+                comma = true;
+            }
+        } else {
+            return Err(ParseError::InvalidChar { found: c, position });
+        }
+    }
+    match current {
+        Some((joltage, _)) => joltages.push(joltage),
+        None if comma => {
+            return Err(ParseError::MissingJoltage {
+                position: s.chars().count(),
+            });
+        }
+        None => {}
+    }
+    Ok(joltages)
+}
+
+/// Finds the index of the highest single digit joltage, taking the leftmost if there is a tie.
+#[cfg(test)]
+fn find_highest_index_from_left(range: &[u32]) -> usize {
+    let mut best_index = 0;
+    let mut best_value = 0;
+
+    for (i, value) in range.iter().enumerate() {
+        if *value == 9 {
+            return i; // Special case since 9 is the highest value.
+        }
+        if *value > best_value {
+            best_index = i;
+            best_value = *value;
+        }
+    }
+
+    best_index
+}
+/// The number of decimal digits in a joltage.
+pub(crate) fn width(joltage: u32) -> u32 {
+    joltage.checked_ilog10().unwrap_or(0) + 1
 }
 
 #[cfg(test)]
@@ -70,8 +428,197 @@ mod tests {
         );
     }
     #[test]
+    fn test_all_batteries_from_normalized_windows_input() {
+        let input = common::input::normalize("123\r\n456\r\n", NORMALIZATION);
+        let all_batteries = AllBatteries::from_str(&input).unwrap();
+        assert_eq!(
+            all_batteries.0,
+            vec![BatteryBank(vec![1, 2, 3]), BatteryBank(vec![4, 5, 6])]
+        );
+    }
+    #[test]
     fn test_battery_bank_from_str() {
         let battery_bank = BatteryBank::from_str("123").unwrap();
         assert_eq!(battery_bank.0, vec![1, 2, 3]);
     }
+    #[test]
+    fn test_battery_bank_parse_delimited() {
+        let bank = BatteryBank::from_str("12,7,130,4").unwrap();
+        assert_eq!(bank.0, vec![12, 7, 130, 4]);
+        let bank = BatteryBank::from_str("12  7, 130 ,4").unwrap();
+        assert_eq!(bank.0, vec![12, 7, 130, 4]);
+        // Spaces alone are enough to split joltages, and single digits can be written either way.
+        let bank = BatteryBank::parse("1 2 3", BankFormat::Delimited).unwrap();
+        assert_eq!(bank, BatteryBank::from_str("123").unwrap());
+        let bank = BatteryBank::parse("123", BankFormat::Delimited).unwrap();
+        assert_eq!(bank.0, vec![123]);
+        let all_batteries = AllBatteries::from_str("12,7\n345").unwrap();
+        assert_eq!(
+            all_batteries.0,
+            vec![BatteryBank(vec![12, 7]), BatteryBank(vec![3, 4, 5])]
+        );
+    }
+    #[test]
+    fn test_battery_bank_parse_errors() {
+        assert_eq!(
+            BatteryBank::from_str("12a3"),
+            Err(ParseError::InvalidChar {
+                found: 'a',
+                position: 2
+            })
+        );
+        assert_eq!(
+            BatteryBank::parse("1,2", BankFormat::Digits),
+            Err(ParseError::InvalidChar {
+                found: ',',
+                position: 1
+            })
+        );
+        assert_eq!(
+            BatteryBank::from_str("1, 2,-3"),
+            Err(ParseError::InvalidChar {
+                found: '-',
+                position: 5
+            })
+        );
+        // Whitespace only splits joltages when there are digits either side of it.
+        assert_eq!(
+            BatteryBank::from_str("123 "),
+            Err(ParseError::InvalidChar {
+                found: ' ',
+                position: 3
+            })
+        );
+        assert_eq!(
+            BatteryBank::from_str("\t12"),
+            Err(ParseError::InvalidChar {
+                found: '\t',
+                position: 0
+            })
+        );
+        assert_eq!(
+            BatteryBank::from_str("12 a"),
+            Err(ParseError::InvalidChar {
+                found: ' ',
+                position: 2
+            })
+        );
+        assert_eq!(BatteryBank::from_str("12 \t 7").unwrap().0, vec![12, 7]);
+        assert_eq!(
+            BatteryBank::from_str("1,,2"),
+            Err(ParseError::MissingJoltage { position: 2 })
+        );
+        assert_eq!(
+            BatteryBank::from_str(",1"),
+            Err(ParseError::MissingJoltage { position: 0 })
+        );
+        assert_eq!(
+            BatteryBank::from_str("1,2,"),
+            Err(ParseError::MissingJoltage { position: 4 })
+        );
+        assert_eq!(
+            BatteryBank::from_str("1,99999999999"),
+            Err(ParseError::JoltageTooLarge { position: 2 })
+        );
+    }
+    #[test]
+    fn test_bank_format_from_str() {
+        for format in [BankFormat::Auto, BankFormat::Digits, BankFormat::Delimited] {
+            assert_eq!(format.to_string().parse::<BankFormat>(), Ok(format));
+        }
+        assert!("csv".parse::<BankFormat>().is_err());
+        assert_eq!(" Sum".parse::<Combine>(), Ok(Combine::Sum));
+        assert!("product".parse::<Combine>().is_err());
+    }
+    #[test]
+    fn test_find_highest_index_from_left() {
+        let input = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
+        assert_eq!(find_highest_index_from_left(&input), 8);
+    }
+    #[test]
+    fn test_find_highest_index_from_left_early_exit() {
+        let input = vec![1, 2, 3, 9, 5, 6, 7, 8, 9];
+        assert_eq!(find_highest_index_from_left(&input), 3);
+    }
+    #[test]
+    fn test_max_joltage() {
+        let bank = BatteryBank::from_str("818181911112111").unwrap();
+        assert_eq!(bank.max_joltage(2), Ok(92));
+        assert_eq!(bank.max_joltage(12), Ok(888911112111));
+        assert_eq!(bank.max_joltage(15), Ok(818181911112111));
+        assert_eq!(bank.max_joltage(0), Ok(0));
+    }
+    #[test]
+    fn test_stack_matches_sliding() {
+        // A simple LCG so the banks are the same every run.
+        let mut state = 7u64;
+        let mut next = || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            (state >> 33) as u32
+        };
+        for _ in 0..200 {
+            let len = next() as usize % 25 + 1;
+            let bank = BatteryBank((0..len).map(|_| next() % 10).collect());
+            for k in 0..=len {
+                assert_eq!(bank.select(k), bank.select_sliding(k), "{bank:?} {k}");
+            }
+        }
+    }
+    #[test]
+    fn test_select_ties_go_left() {
+        let bank = BatteryBank::from_str("999").unwrap();
+        assert_eq!(bank.select(2).unwrap().positions, vec![0, 1]);
+        let bank = BatteryBank::from_str("7177").unwrap();
+        let selection = bank.select(2).unwrap();
+        assert_eq!(selection.positions, vec![0, 2]);
+        assert_eq!(selection.digits, vec![7, 7]);
+    }
+    #[test]
+    fn test_max_joltage_digits_long() {
+        // 100k batteries of a repeating 1-9 pattern with the 9s kept, which no u64 could hold.
+        let bank = BatteryBank((0..100_000).map(|i| i % 9 + 1).collect());
+        let digits = bank.max_joltage_digits(5000).unwrap();
+        assert_eq!(digits.len(), 5000);
+        // There are 11111 nines, so the best joltage is the last 5000 of them.
+        assert!(digits.iter().all(|&d| d == 9));
+        let digits = bank.max_joltage_digits(99_999).unwrap();
+        assert_eq!(digits[0], 2);
+    }
+    #[test]
+    fn test_max_joltage_errors() {
+        let bank = BatteryBank::from_str("123").unwrap();
+        assert_eq!(
+            bank.max_joltage(4),
+            Err(JoltageError::BankTooShort {
+                needed: 4,
+                available: 3
+            })
+        );
+        let bank = BatteryBank(vec![9; 30]);
+        assert_eq!(bank.max_joltage(20), Err(JoltageError::TooManyDigits(20)));
+        assert_eq!(bank.max_joltage(19), Ok(9_999_999_999_999_999_999));
+        // Two joltages of ten digits each make twenty.
+        let bank = BatteryBank::from_str("4000000000,4000000000").unwrap();
+        assert_eq!(bank.max_joltage(2), Err(JoltageError::TooManyDigits(20)));
+        // A leading zero doesn't count towards the digits.
+        let bank = BatteryBank::from_str("0,123456789,1234567890").unwrap();
+        assert_eq!(bank.max_joltage(3), Ok(1_234_567_891_234_567_890));
+        let bank = BatteryBank(vec![0; 25]);
+        assert_eq!(bank.max_joltage(20), Ok(0));
+    }
+    #[test]
+    fn test_select_multi_digit() {
+        // A longer output is always bigger, so the 130 is worth more than the 7 in front of it.
+        let bank = BatteryBank::from_str("12,7,130,4").unwrap();
+        assert_eq!(bank.select(2).unwrap().positions, vec![0, 2]);
+        assert_eq!(bank.max_joltage(2), Ok(12130));
+        assert_eq!(bank.max_joltage(3), Ok(127130));
+        // With joltages all the same length, the stack gives the same answer.
+        let bank = BatteryBank::from_str("31,45,12,45").unwrap();
+        assert_eq!(bank.select(2).unwrap().joltages, vec![45, 45]);
+        assert_eq!(
+            bank.output_joltage(2, &SelectOptions::new().sum()),
+            Ok(Joltage::from(90))
+        );
+    }
 }
@@ -1,65 +1,48 @@
-use std::str::FromStr;
-
 use thiserror::Error;
 
-use crate::{AllBatteries, BatteryBank};
+use crate::{
+    AllBatteries, BankFormat, BatteryBank, Joltage, JoltageError, PART_1_LENGTH as LENGTH,
+    SelectOptions,
+};
 
 #[derive(Debug, Error)]
 pub enum PartError {
     #[error(transparent)]
     ParsingError(#[from] crate::ParseError),
+    #[error(transparent)]
+    JoltageError(#[from] JoltageError),
 }
 
-pub fn run(input: &str) -> Result<u64, PartError> {
-    let all_battery_banks = AllBatteries::from_str(input)?;
-    let total_output_joltage = get_total_output_joltage_of_all_banks(&all_battery_banks);
-    Ok(total_output_joltage as u64)
+pub fn run(input: &str, format: BankFormat, options: &SelectOptions) -> Result<Joltage, PartError> {
+    let all_battery_banks = AllBatteries::parse(input, format)?;
+    let total_output_joltage = get_total_output_joltage_of_all_banks(&all_battery_banks, options)?;
+    Ok(total_output_joltage)
 }
 
-fn get_total_output_joltage_of_all_banks(all_battery_banks: &AllBatteries) -> u32 {
+/// Same as `run`, but each bank is parsed and measured one line at a time.
+pub fn run_streaming<'a>(
+    lines: impl Iterator<Item = &'a str>,
+    format: BankFormat,
+    options: &SelectOptions,
+) -> Result<Joltage, PartError> {
+    let mut total_output_joltage = Joltage::default();
+    for line in lines.filter(|ln| !ln.is_empty()) {
+        let bank = BatteryBank::parse(line, format)?;
+        total_output_joltage += &bank.output_joltage(LENGTH, options)?;
+    }
+    Ok(total_output_joltage)
+}
+
+fn get_total_output_joltage_of_all_banks(
+    all_battery_banks: &AllBatteries,
+    options: &SelectOptions,
+) -> Result<Joltage, JoltageError> {
     all_battery_banks
         .iter()
-        .map(|bank| get_largest_joltage_from_battery_bank(bank))
+        .map(|bank| bank.output_joltage(LENGTH, options))
         .sum()
 }
 
-fn get_largest_joltage_from_battery_bank(battery: &BatteryBank) -> u32 {
-    let joltages = battery.joltages();
-    // We can exclude the final joltage from the slice since it cannot be the first element.
-    let first_slice = &joltages[..joltages.len() - 1];
-    let first_index = find_highest_index_from_left(first_slice);
-    // Then we get a slice from the first index (but not including it) to the end of the slice.
-    let second_slice = &joltages[first_index + 1..];
-    let second_index = find_highest_index_from_left(second_slice);
-
-    let a = first_slice[first_index];
-    let b = second_slice[second_index];
-
-    combine_integers(a, b)
-}
-
-fn find_highest_index_from_left(range: &[u32]) -> usize {
-    let mut best_index = 0;
-    let mut best_value = 0;
-
-    for (i, value) in range.iter().enumerate() {
-        if *value == 9 {
-            return i; // Special case since 9 is the highest value.
-        }
-        if *value > best_value {
-            best_index = i;
-            best_value = *value;
-        }
-    }
-
-    best_index
-}
-/// Combines two integers `a` and `b` into `ab` without using string conversion.
-/// works as long as both `a` and `b` are less than 10.
-fn combine_integers(a: u32, b: u32) -> u32 {
-    a * 10 + b
-}
-
 #[cfg(test)]
 mod tests {
     use crate::AllBatteries;
@@ -77,35 +60,55 @@ mod tests {
     }
 
     #[test]
-    fn test_find_highest_index_from_left() {
-        let input = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
-        assert_eq!(find_highest_index_from_left(&input), 8);
+    fn test_get_largest_joltage_from_battery_bank() {
+        let battery_bank = BatteryBank(vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let largest_joltage = battery_bank.max_joltage(LENGTH).unwrap();
+        assert_eq!(largest_joltage, 89);
     }
 
     #[test]
-    fn test_find_highest_index_from_left_early_exit() {
-        let input = vec![1, 2, 3, 9, 5, 6, 7, 8, 9];
-        assert_eq!(find_highest_index_from_left(&input), 3);
+    fn test_get_total_output_joltage_of_all_banks() {
+        let all_battery_banks = setup();
+        let total_output_joltage =
+            get_total_output_joltage_of_all_banks(&all_battery_banks, &SelectOptions::new());
+        assert_eq!(total_output_joltage, Ok(Joltage::from(98 + 89 + 78 + 92)));
     }
 
     #[test]
-    fn test_combine_integers() {
-        assert_eq!(combine_integers(1, 2), 12);
-        assert_eq!(combine_integers(9, 8), 98);
-        assert_eq!(combine_integers(5, 5), 55);
+    fn test_run_delimited() {
+        let input = "12,7,130,4\n5 60 7";
+        let options = SelectOptions::new();
+        let total = run(input, BankFormat::Auto, &options).unwrap();
+        assert_eq!(total, Joltage::from(12130 + 607));
+        let total = run(input, BankFormat::Delimited, &options.clone().sum()).unwrap();
+        assert_eq!(total, Joltage::from(142 + 67));
+        // Read as digits, the commas aren't joltages.
+        let result = run(input, BankFormat::Digits, &options);
+        assert!(matches!(
+            result,
+            Err(PartError::ParsingError(crate::ParseError::InvalidChar {
+                found: ',',
+                position: 2
+            }))
+        ));
     }
 
     #[test]
-    fn test_get_largest_joltage_from_battery_bank() {
-        let battery_bank = BatteryBank(vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
-        let largest_joltage = get_largest_joltage_from_battery_bank(&battery_bank);
-        assert_eq!(largest_joltage, 89);
+    fn test_run_bank_too_short() {
+        let result = run("123\n4", BankFormat::Auto, &SelectOptions::new());
+        assert!(matches!(
+            result,
+            Err(PartError::JoltageError(JoltageError::BankTooShort {
+                needed: 2,
+                available: 1
+            }))
+        ));
     }
 
     #[test]
-    fn test_get_total_output_joltage_of_all_banks() {
-        let all_battery_banks = setup();
-        let total_output_joltage = get_total_output_joltage_of_all_banks(&all_battery_banks);
-        assert_eq!(total_output_joltage, 98 + 89 + 78 + 92);
+    fn test_run_streaming() {
+        let input = include_str!("../inputs/test.txt");
+        let total = run_streaming(input.lines(), BankFormat::Auto, &SelectOptions::new());
+        assert_eq!(total.unwrap(), Joltage::from(357));
     }
 }
@@ -1,80 +1,53 @@
-use log::info;
-use std::str::FromStr;
 use thiserror::Error;
 
-use crate::{AllBatteries, BatteryBank};
+use crate::{AllBatteries, BankFormat, BatteryBank, Joltage, JoltageError, SelectOptions};
 
-// Part 2 is almost the exact same as part 1, but the main code has been generalised to work for any length of joltage output.
-// Also, I was getting overflows during the number combination process, so I changed from `u32` to `u64` which seems slightly hacky,
-// but it works pretty quickly still.
-
-/// The required length of the joltage output in digits.
-/// By changing it back to 2, it can also solve part 1!
-const LENGTH: usize = 12;
+// Part 2 is the same as part 1 but with more batteries, so both use `BatteryBank::output_joltage`.
 
 #[derive(Debug, Error)]
 pub enum PartError {
     #[error(transparent)]
     ParsingError(#[from] crate::ParseError),
+    #[error(transparent)]
+    JoltageError(#[from] JoltageError),
 }
 
-pub fn run(input: &str) -> Result<u64, PartError> {
-    let all_battery_banks = AllBatteries::from_str(input)?;
-    let total_output_joltage = get_total_output_joltage_of_all_banks(&all_battery_banks);
-    Ok(total_output_joltage as u64)
-}
-
-fn get_total_output_joltage_of_all_banks(all_battery_banks: &AllBatteries) -> u64 {
-    all_battery_banks
-        .iter()
-        .map(|bank| get_largest_joltage_from_battery_bank(bank))
-        .sum()
+pub fn run(
+    input: &str,
+    length: usize,
+    format: BankFormat,
+    options: &SelectOptions,
+) -> Result<Joltage, PartError> {
+    let all_battery_banks = AllBatteries::parse(input, format)?;
+    let total_output_joltage =
+        get_total_output_joltage_of_all_banks(&all_battery_banks, length, options)?;
+    Ok(total_output_joltage)
 }
 
-fn get_largest_joltage_from_battery_bank(battery: &BatteryBank) -> u64 {
-    let joltages = battery.joltages();
-    let mut output = 0;
-
-    let mut current_index = 0;
-    for i in 0..LENGTH {
-        // We don't need to search the entire vec, we can start at the index of the previous highest value (since it cannot be left of it).
-        // And we don't need to go all the way to the end, because the highest value will always be at least LENGTH^10 (assuming no 0 in the input)
-        // so the first value will always be at least that far to the left.
-        // After that we just "slide" right by 1 every iteration. It seems to work pretty well.
-        let slice = &joltages[current_index..(joltages.len() - LENGTH + i + 1)];
-        // Get the highest value from the slice and cache the index.
-        let index = find_highest_index_from_left(slice);
-        let value = slice[index] as u64;
-        output = combine_integers(output, value);
-        // Logging for some troubleshooting
-        info!("slice: {slice:?}, index: {index}, value: {value}, output: {output}");
-        // Move the current index forward by the value of the highest index + 1.
-        current_index += index + 1;
+/// Same as `run`, but each bank is parsed and measured one line at a time.
+pub fn run_streaming<'a>(
+    lines: impl Iterator<Item = &'a str>,
+    length: usize,
+    format: BankFormat,
+    options: &SelectOptions,
+) -> Result<Joltage, PartError> {
+    let mut total_output_joltage = Joltage::default();
+    for line in lines.filter(|ln| !ln.is_empty()) {
+        let bank = BatteryBank::parse(line, format)?;
+        total_output_joltage += &bank.output_joltage(length, options)?;
     }
-    output
+    Ok(total_output_joltage)
 }
 
-fn find_highest_index_from_left(range: &[u32]) -> usize {
-    let mut best_index = 0;
-    let mut best_value = 0;
-
-    for (i, value) in range.iter().enumerate() {
-        if *value == 9 {
-            return i; // Special case since 9 is the highest value.
-        }
-        if *value > best_value {
-            best_index = i;
-            best_value = *value;
-        }
-    }
-
-    best_index
-}
-/// Combines two integers `a` and `b` into `ab` without using string conversion.
-/// works as long as both `a` and `b` are less than 10.
-fn combine_integers(a: u64, b: u64) -> u64 {
-    a as u64;
-    a * 10 + b
+fn get_total_output_joltage_of_all_banks(
+    all_battery_banks: &AllBatteries,
+    length: usize,
+    options: &SelectOptions,
+) -> Result<Joltage, JoltageError> {
+    all_battery_banks
+        .iter()
+        .map(|bank| bank.output_joltage(length, options))
+        .sum()
 }
 
 #[cfg(test)]
@@ -94,35 +67,73 @@ mod tests {
     }
 
     #[test]
-    fn test_find_highest_index_from_left() {
-        let input = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
-        assert_eq!(find_highest_index_from_left(&input), 8);
+    fn test_get_largest_joltage_from_battery_bank() {
+        let battery_bank = BatteryBank(vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let largest_joltage = battery_bank.max_joltage(2).unwrap();
+        assert_eq!(largest_joltage, 89);
+    }
+
+    #[test]
+    fn test_get_total_output_joltage_of_all_banks() {
+        let all_battery_banks = setup();
+        let total_output_joltage =
+            get_total_output_joltage_of_all_banks(&all_battery_banks, 2, &SelectOptions::new());
+        assert_eq!(total_output_joltage, Ok(Joltage::from(98 + 89 + 78 + 92)));
     }
 
     #[test]
-    fn test_find_highest_index_from_left_early_exit() {
-        let input = vec![1, 2, 3, 9, 5, 6, 7, 8, 9];
-        assert_eq!(find_highest_index_from_left(&input), 3);
+    fn test_get_total_output_joltage_of_all_banks_length_12() {
+        let all_battery_banks = setup();
+        let total_output_joltage =
+            get_total_output_joltage_of_all_banks(&all_battery_banks, 12, &SelectOptions::new());
+        assert_eq!(total_output_joltage, Ok(Joltage::from(3121910778619)));
     }
 
     #[test]
-    fn test_combine_integers() {
-        assert_eq!(combine_integers(1, 2), 12);
-        assert_eq!(combine_integers(9, 8), 98);
-        assert_eq!(combine_integers(5, 5), 55);
+    fn test_more_than_19_batteries() {
+        let bank = "1".repeat(10) + &"9".repeat(30);
+        let total = run(
+            &format!("{bank}\n{bank}"),
+            25,
+            BankFormat::Auto,
+            &SelectOptions::new(),
+        )
+        .unwrap();
+        // Two lots of 25 nines.
+        assert_eq!(total.to_string(), format!("1{}8", "9".repeat(24)));
     }
 
     #[test]
-    fn test_get_largest_joltage_from_battery_bank() {
-        let battery_bank = BatteryBank(vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
-        let largest_joltage = get_largest_joltage_from_battery_bank(&battery_bank);
-        assert_eq!(largest_joltage, 89);
+    fn test_run_delimited_sum() {
+        // The three biggest joltages, wherever they are.
+        let total = run(
+            "5,300,2,41,300",
+            3,
+            BankFormat::Auto,
+            &SelectOptions::new().sum(),
+        );
+        assert_eq!(total.unwrap(), Joltage::from(641));
+        let total = run("5,300,2,41,300", 3, BankFormat::Auto, &SelectOptions::new());
+        assert_eq!(total.unwrap(), Joltage::from(30_041_300));
     }
 
     #[test]
-    fn test_get_total_output_joltage_of_all_banks() {
-        let all_battery_banks = setup();
-        let total_output_joltage = get_total_output_joltage_of_all_banks(&all_battery_banks);
-        assert_eq!(total_output_joltage, 98 + 89 + 78 + 92);
+    fn test_run_bank_too_short() {
+        let result = run("123\n456", 4, BankFormat::Auto, &SelectOptions::new());
+        assert!(matches!(
+            result,
+            Err(PartError::JoltageError(JoltageError::BankTooShort {
+                needed: 4,
+                available: 3
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_run_streaming() {
+        let input = include_str!("../inputs/test.txt");
+        let total =
+            run_streaming(input.lines(), 12, BankFormat::Auto, &SelectOptions::new()).unwrap();
+        assert_eq!(total.to_u64(), Some(3121910778619));
     }
 }
@@ -0,0 +1,117 @@
+use crate::{BatteryBank, Combine, Joltage};
+
+/// The batteries picked in a bank, in the order they appear.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    /// Where each picked battery is in the bank, counting from 0.
+    pub positions: Vec<usize>,
+    /// The joltage of each picked battery.
+    pub joltages: Vec<u32>,
+    /// The digits of the picked joltages one after another, which are the digits of the bank's output.
+    pub digits: Vec<u8>,
+}
+
+/// How `Selection::highlight` marks the picked batteries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    /// Bold green, for a terminal.
+    Ansi,
+    /// Square brackets around each picked battery, for anywhere colours don't show.
+    Brackets,
+}
+
+impl Selection {
+    pub(crate) fn new(bank: &BatteryBank, positions: Vec<usize>) -> Self {
+        let joltages = positions
+            .iter()
+            .map(|&position| bank.joltages()[position])
+            .collect::<Vec<_>>();
+        let digits = joltages
+            .iter()
+            .flat_map(|joltage| joltage.to_string().into_bytes())
+            .map(|digit| digit - b'0')
+            .collect();
+        Self {
+            positions,
+            joltages,
+            digits,
+        }
+    }
+    /// The picked joltages read as the digits of one number.
+    pub fn joltage(&self) -> Joltage {
+        Joltage::from_digits(&self.digits)
+    }
+    /// The picked joltages added up.
+    pub fn sum(&self) -> Joltage {
+        Joltage::from(
+            self.joltages
+                .iter()
+                .map(|&joltage| joltage as u64)
+                .sum::<u64>(),
+        )
+    }
+    pub fn output(&self, combine: Combine) -> Joltage {
+        match combine {
+            Combine::Concatenate => self.joltage(),
+            Combine::Sum => self.sum(),
+        }
+    }
+    /// Writes out the whole bank with the picked batteries marked. If any joltage has more than one digit
+    /// they are split by commas, so that it's clear where each one ends.
+    pub fn highlight(&self, bank: &BatteryBank, style: Highlight) -> String {
+        let split = bank.joltages().iter().any(|&joltage| joltage > 9);
+        let mut picked = self.positions.iter().peekable();
+        let mut out = String::new();
+        for (position, joltage) in bank.joltages().iter().enumerate() {
+            if split && position > 0 {
+                out.push(',');
+            }
+            if picked.next_if_eq(&&position).is_some() {
+                match style {
+                    Highlight::Ansi => out.push_str(&format!("\x1b[1;32m{joltage}\x1b[0m")),
+                    Highlight::Brackets => out.push_str(&format!("[{joltage}]")),
+                }
+            } else {
+                out.push_str(&joltage.to_string());
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_highlight() {
+        let bank = BatteryBank::from_str("818181911112111").unwrap();
+        let selection = bank.select(2).unwrap();
+        assert_eq!(selection.positions, vec![6, 11]);
+        assert_eq!(
+            selection.highlight(&bank, Highlight::Brackets),
+            "818181[9]1111[2]111"
+        );
+        assert_eq!(
+            selection.highlight(&bank, Highlight::Ansi),
+            "818181\x1b[1;32m9\x1b[0m1111\x1b[1;32m2\x1b[0m111"
+        );
+        assert_eq!(selection.joltage().to_u64(), Some(92));
+    }
+
+    #[test]
+    fn test_multi_digit() {
+        let bank = BatteryBank::from_str("12,7,130,4").unwrap();
+        let selection = bank.select(2).unwrap();
+        assert_eq!(selection.joltages, vec![12, 130]);
+        assert_eq!(selection.digits, vec![1, 2, 1, 3, 0]);
+        assert_eq!(selection.output(Combine::Concatenate).to_u64(), Some(12130));
+        assert_eq!(selection.output(Combine::Sum).to_u64(), Some(142));
+        assert_eq!(
+            selection.highlight(&bank, Highlight::Brackets),
+            "[12],7,[130],4"
+        );
+    }
+}
@@ -1,10 +1,14 @@
 use std::{collections::HashMap, str::FromStr};
 
 use anyhow::{Context, Result};
+pub use common::input::Normalization;
+use common::params::ParamSpec;
+pub use common::params::Params;
 use thiserror::Error;
 
 mod part_1;
 mod part_2;
+mod visualize;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct IVec2 {
@@ -85,14 +89,25 @@ pub enum ParseError {
     InvalidCharacter(char),
 }
 
-pub fn run_part_1(input: &str) -> Result<u64> {
+/// How the input is cleaned up before it is parsed.
+pub const NORMALIZATION: Normalization = Normalization::Full;
+
+/// This day has no tunable parameters.
+pub const PARAMS: &[&dyn ParamSpec] = &[];
+
+pub fn run_part_1(input: &str, _params: &Params) -> Result<u64> {
     part_1::run(input).context("Failed to run part 1")
 }
 
-pub fn run_part_2(input: &str) -> Result<u64> {
+pub fn run_part_2(input: &str, _params: &Params) -> Result<u64> {
     part_2::run(input).context("Failed to run part 2")
 }
 
+/// Draws the grid as an SVG picture for `--visualize`.
+pub fn visualize(input: &str, _params: &Params) -> Result<String> {
+    visualize::render(input).context("Failed to visualize")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,3 +1,4 @@
+use common::explain::{self, Event};
 use log::info;
 use thiserror::Error;
 
@@ -17,6 +18,7 @@ pub fn run(input: &str) -> Result<u64, PartError> {
 
 pub fn count_all_nodes_that_can_be_removed_in_grid(grid: &mut Grid) -> u64 {
     let mut count = 0;
+    let mut round = 0;
     loop {
         let accessible_nodes = get_all_accessible_nodes(grid);
         if accessible_nodes.is_empty() {
@@ -24,6 +26,14 @@ pub fn count_all_nodes_that_can_be_removed_in_grid(grid: &mut Grid) -> u64 {
         }
         count += accessible_nodes.len() as u64;
         remove_all_nodes(grid, &accessible_nodes);
+        round += 1;
+        explain::record(|| {
+            Event::new("removal_round")
+                .with("round", round)
+                .with("removed", accessible_nodes.len())
+                .with("remaining", grid.nodes.len())
+                .with("total_removed", count)
+        });
     }
 
     count
@@ -0,0 +1,39 @@
+use common::svg::Svg;
+
+use crate::*;
+
+const ACCESSIBLE: &str = "#e07b39";
+const BLOCKED: &str = "#999999";
+
+/// Draws the grid, with the paper rolls that part 1 can reach picked out.
+pub fn render(input: &str) -> Result<String, ParseError> {
+    let grid = Grid::from_str(input)?;
+    let width = input.trim().lines().map(str::len).max().unwrap_or(0);
+    let height = input.trim().lines().count();
+    // Sorted so the same input always draws the same picture.
+    let mut rolls = grid.nodes.iter().collect::<Vec<_>>();
+    rolls.sort_by_key(|(pos, _)| (pos.y, pos.x));
+    let mut svg = Svg::new(0.0, 0.0, width as f64, height as f64);
+    for (pos, node) in rolls {
+        let fill = if count_node_neighbours(&grid, node) < 4 {
+            ACCESSIBLE
+        } else {
+            BLOCKED
+        };
+        svg = svg.rect(pos.x as f64 + 0.05, pos.y as f64 + 0.05, 0.9, 0.9, fill);
+    }
+    Ok(svg.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let svg = render(include_str!("../inputs/test.txt")).unwrap();
+        assert!(svg.contains(r#"viewBox="0 0 10 10""#));
+        assert_eq!(svg.matches("<rect").count(), 71);
+        assert_eq!(svg.matches(ACCESSIBLE).count(), 13);
+    }
+}
@@ -1,6 +1,9 @@
 use std::{num::ParseIntError, str::FromStr};
 
 use anyhow::{Context, Result};
+pub use common::input::Normalization;
+use common::params::ParamSpec;
+pub use common::params::Params;
 use thiserror::Error;
 
 mod part_1;
@@ -35,6 +38,9 @@ impl IdRange {
     pub fn len(&self) -> usize {
         self.max - self.min + 1
     }
+    pub fn is_empty(&self) -> bool {
+        self.max < self.min
+    }
 }
 
 pub type IngredientId = usize;
@@ -59,16 +65,33 @@ impl FromStr for Database {
 fn parse_ranges(s: &str) -> Result<Vec<IdRange>, ParseError> {
     let mut set = Vec::new();
     for raw_range in s.lines() {
-        let (min, max) = raw_range
-            .split_once('-')
-            .ok_or(ParseError::NoRangeSeperator)?;
-        let min = IngredientId::from_str(min)?;
-        let max = IngredientId::from_str(max)?;
-        let range = IdRange { min, max };
-        set.push(range);
+        set.push(parse_range(raw_range)?);
     }
     Ok(set)
 }
+fn parse_range(raw_range: &str) -> Result<IdRange, ParseError> {
+    let (min, max) = raw_range
+        .split_once('-')
+        .ok_or(ParseError::NoRangeSeperator)?;
+    let min = IngredientId::from_str(min)?;
+    let max = IngredientId::from_str(max)?;
+    Ok(IdRange { min, max })
+}
+/// Reads the fresh ID ranges from the start of a stream of lines, stopping at the blank line that
+/// separates them from the available IDs. Whatever is left in `lines` is the available IDs.
+pub fn take_fresh_id_ranges<'a>(
+    lines: &mut impl Iterator<Item = &'a str>,
+) -> Result<Vec<IdRange>, ParseError> {
+    let mut set = Vec::new();
+    let mut lines = lines.skip_while(|ln| ln.is_empty());
+    loop {
+        match lines.next() {
+            Some("") => return Ok(set),
+            Some(raw_range) => set.push(parse_range(raw_range)?),
+            None => return Err(ParseError::NoDBSeperator),
+        }
+    }
+}
 fn parse_available(s: &str) -> Result<Vec<IngredientId>, ParseError> {
     let mut available = Vec::new();
     for raw_id in s.lines() {
@@ -88,14 +111,30 @@ pub enum ParseError {
     BadID(#[from] ParseIntError),
 }
 
-pub fn run_part_1(input: &str) -> Result<u64> {
+/// How the input is cleaned up before it is parsed.
+pub const NORMALIZATION: Normalization = Normalization::Full;
+
+/// This day has no tunable parameters.
+pub const PARAMS: &[&dyn ParamSpec] = &[];
+
+pub fn run_part_1(input: &str, _params: &Params) -> Result<u64> {
     part_1::run(input).context("Failed to run part 1")
 }
 
-pub fn run_part_2(input: &str) -> Result<u64> {
+pub fn run_part_2(input: &str, _params: &Params) -> Result<u64> {
     part_2::run(input).context("Failed to run part 2")
 }
 
+/// Runs part 1 checking one available ID at a time, for inputs too big to load all at once.
+pub fn stream_part_1<'a>(lines: impl Iterator<Item = &'a str>, _params: &Params) -> Result<u64> {
+    part_1::run_streaming(lines).context("Failed to run part 1")
+}
+
+/// Part 2 only needs the ranges, so this reads those and never looks at the available IDs.
+pub fn stream_part_2<'a>(lines: impl Iterator<Item = &'a str>, _params: &Params) -> Result<u64> {
+    part_2::run_streaming(lines).context("Failed to run part 2")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,4 +155,27 @@ mod tests {
         let db_actual = Database::from_str(db_string).unwrap();
         assert_eq!(db_expected, db_actual);
     }
+
+    #[test]
+    fn test_parse_normalized_windows_input() {
+        let db_string = common::input::normalize("\u{feff}1-9\r\n\r\n3 \r\n7\r\n", NORMALIZATION);
+        let db_expected = create_minimal_test_db();
+        let db_actual = Database::from_str(&db_string).unwrap();
+        assert_eq!(db_expected, db_actual);
+    }
+
+    #[test]
+    fn test_take_fresh_id_ranges() {
+        let mut lines = ["", "1-9", "12-15", "", "3", "7"].into_iter();
+        let ranges = take_fresh_id_ranges(&mut lines).unwrap();
+        assert_eq!(ranges, vec![IdRange::new(1, 9), IdRange::new(12, 15)]);
+        assert_eq!(lines.collect::<Vec<_>>(), vec!["3", "7"]);
+    }
+
+    #[test]
+    fn test_take_fresh_id_ranges_no_separator() {
+        let mut lines = ["1-9", "12-15"].into_iter();
+        let result = take_fresh_id_ranges(&mut lines);
+        assert!(matches!(result, Err(ParseError::NoDBSeperator)));
+    }
 }
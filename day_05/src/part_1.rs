@@ -14,9 +14,39 @@ pub fn run(input: &str) -> Result<u64, PartError> {
     Ok(count as u64)
 }
 
+/// Same as `run`, but the available IDs are checked one line at a time rather than being collected.
+/// The fresh ranges still have to be read in full first.
+pub fn run_streaming<'a>(mut lines: impl Iterator<Item = &'a str>) -> Result<u64, PartError> {
+    let fresh_id_ranges = take_fresh_id_ranges(&mut lines)?;
+    let db = Database {
+        fresh_id_ranges,
+        available_ids: vec![],
+    };
+    let mut count = 0;
+    for raw_id in lines.filter(|ln| !ln.is_empty()) {
+        let id = IngredientId::from_str(raw_id).map_err(ParseError::from)?;
+        if db.is_fresh(id) {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
 pub fn count_fresh_ingredient_ids_in_db(db: &Database) -> usize {
     db.available_ids
         .iter()
         .filter(|id| db.is_fresh(**id))
         .count()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_streaming_matches_run() {
+        let input = include_str!("../inputs/test.txt");
+        assert_eq!(run(input).unwrap(), 3);
+        assert_eq!(run_streaming(input.lines()).unwrap(), 3);
+    }
+}
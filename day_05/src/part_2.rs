@@ -15,6 +15,13 @@ pub fn run(input: &str) -> Result<u64, PartError> {
     let count = merged.all_ranges_len();
     Ok(count as u64)
 }
+/// Same as `run`, but stops reading once it has the ranges.
+pub fn run_streaming<'a>(mut lines: impl Iterator<Item = &'a str>) -> Result<u64, PartError> {
+    let fresh_id_ranges = take_fresh_id_ranges(&mut lines)?;
+    let merged = MergedIngredientRanges::from_unsorted(fresh_id_ranges);
+    let count = merged.all_ranges_len();
+    Ok(count as u64)
+}
 #[derive(Debug)]
 pub struct MergedIngredientRanges {
     merged: Vec<IdRange>,
@@ -106,7 +113,6 @@ mod tests {
         ];
 
         let merged = MergedIngredientRanges::from_unsorted(unsorted.into());
-        dbg!(&merged);
         let actual = merged.all_ranges_len();
         let expected = 14;
 
@@ -123,7 +129,6 @@ mod tests {
         ];
 
         let merged = MergedIngredientRanges::from_unsorted(unsorted.into());
-        dbg!(&merged);
         let actual = merged.all_ranges_len();
         let expected = 16;
 
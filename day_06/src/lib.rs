@@ -1,16 +1,25 @@
 use std::str::FromStr;
 
 use anyhow::{Context, Result};
+pub use common::input::Normalization;
+use common::params::ParamSpec;
+pub use common::params::Params;
 use thiserror::Error;
 
 mod part_1;
 mod part_2;
 
-pub fn run_part_1(input: &str) -> Result<u64> {
+/// The cephalopod worksheet in part 2 is read column by column, so trailing whitespace has to be kept.
+pub const NORMALIZATION: Normalization = Normalization::LineEndingsOnly;
+
+/// This day has no tunable parameters.
+pub const PARAMS: &[&dyn ParamSpec] = &[];
+
+pub fn run_part_1(input: &str, _params: &Params) -> Result<u64> {
     part_1::run(input).context("Failed to run part 1")
 }
 
-pub fn run_part_2(input: &str) -> Result<u64> {
+pub fn run_part_2(input: &str, _params: &Params) -> Result<u64> {
     part_2::run(input).context("Failed to run part 2")
 }
 
@@ -27,7 +36,7 @@ impl FromStr for Worksheet {
             .next()
             .ok_or(ParseError::MissingOperands)?
             .split_whitespace()
-            .map(|raw_operator| Operator::from_str(raw_operator))
+            .map(Operator::from_str)
             .collect::<Result<Vec<_>, _>>()?;
 
         let mut p_operands = vec![vec![]; operators.len()];
@@ -106,7 +115,42 @@ mod tests {
     use super::*;
 
     fn setup_test_input() -> Worksheet {
-        todo!()
+        Worksheet {
+            problems: vec![
+                Problem {
+                    operands: vec![6, 45, 123],
+                    operator: Operator::Multiply,
+                },
+                Problem {
+                    operands: vec![98, 64, 328],
+                    operator: Operator::Addition,
+                },
+                Problem {
+                    operands: vec![215, 387, 51],
+                    operator: Operator::Multiply,
+                },
+                Problem {
+                    operands: vec![314, 23, 64],
+                    operator: Operator::Addition,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_parse_test_input() {
+        let input = include_str!("../inputs/test.txt");
+        assert_eq!(Worksheet::from_str(input), Ok(setup_test_input()));
+    }
+
+    #[test]
+    fn test_parse_windows_test_input() {
+        let input = format!(
+            "\u{feff}{}",
+            include_str!("../inputs/test.txt").replace('\n', "\r\n")
+        );
+        let input = common::input::normalize(&input, NORMALIZATION);
+        assert_eq!(Worksheet::from_str(&input), Ok(setup_test_input()));
     }
 
     #[test]
@@ -47,9 +47,9 @@ fn cephalopod_worksheet(s: &str) -> std::result::Result<Worksheet, PartError> {
         // a single operand.
         let mut values = vec![Option::None; max_len - 1];
         // Loop row by row through the column.
-        for j in 0..last_line {
+        for (j, line) in lines.iter().take(last_line).enumerate() {
             // If the character is a digit, push it to the values.
-            if let Some(n) = lines[j][i].to_digit(10) {
+            if let Some(n) = line[i].to_digit(10) {
                 values[j] = Some(n as u64);
             }
         }
@@ -82,13 +82,11 @@ fn cephalopod_worksheet(s: &str) -> std::result::Result<Worksheet, PartError> {
 fn combine_numbers(nums: &[Option<u64>]) -> u64 {
     let mut result = 0u64;
 
-    for num in nums {
-        if let Some(n) = num {
-            // Count digits in n
-            let digits = if *n == 0 { 1 } else { n.ilog10() + 1 };
-            // Shift result left by that many digits and add n
-            result = result * 10u64.pow(digits) + n;
-        }
+    for n in nums.iter().flatten() {
+        // Count digits in n
+        let digits = if *n == 0 { 1 } else { n.ilog10() + 1 };
+        // Shift result left by that many digits and add n
+        result = result * 10u64.pow(digits) + n;
     }
 
     result
@@ -96,7 +94,6 @@ fn combine_numbers(nums: &[Option<u64>]) -> u64 {
 
 pub fn run(input: &str) -> Result<u64, PartError> {
     let worksheet = cephalopod_worksheet(input)?;
-    dbg!(&worksheet);
     let score = worksheet
         .problems
         .iter()
@@ -116,23 +113,32 @@ mod tests {
         let expected = Worksheet {
             problems: vec![
                 Problem {
-                    operands: vec![54],
+                    operands: vec![45],
                     operator: Operator::Multiply,
                 },
                 Problem {
-                    operands: vec![43],
+                    operands: vec![34],
                     operator: Operator::Multiply,
                 },
                 Problem {
-                    operands: vec![32],
+                    operands: vec![23],
                     operator: Operator::Addition,
                 },
                 Problem {
-                    operands: vec![21],
+                    operands: vec![12],
                     operator: Operator::Addition,
                 },
             ],
         };
         assert_eq!(actual, Ok(expected));
     }
+
+    #[test]
+    fn test_windows_input_keeps_columns() {
+        let input = include_str!("../inputs/test.txt");
+        let windows = format!("\u{feff}{}", input.replace('\n', "\r\n"));
+        let windows = common::input::normalize(&windows, NORMALIZATION);
+        assert_eq!(run(&windows), Ok(3263827));
+        assert_eq!(run(input), Ok(3263827));
+    }
 }
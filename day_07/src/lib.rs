@@ -1,19 +1,35 @@
 use std::{collections::HashSet, str::FromStr};
 
 use anyhow::{Context, Result};
+use common::explain::{self, Event};
+pub use common::input::Normalization;
+use common::params::ParamSpec;
+pub use common::params::Params;
 use thiserror::Error;
 
 mod part_1;
 mod part_2;
+mod visualize;
 
-pub fn run_part_1(input: &str) -> Result<u64> {
+/// How the input is cleaned up before it is parsed.
+pub const NORMALIZATION: Normalization = Normalization::Full;
+
+/// This day has no tunable parameters.
+pub const PARAMS: &[&dyn ParamSpec] = &[];
+
+pub fn run_part_1(input: &str, _params: &Params) -> Result<u64> {
     part_1::run(input).context("Failed to run part 1")
 }
 
-pub fn run_part_2(input: &str) -> Result<u64> {
+pub fn run_part_2(input: &str, _params: &Params) -> Result<u64> {
     part_2::run(input).context("Failed to run part 2")
 }
 
+/// Draws the manifold and its beams as an SVG picture for `--visualize`.
+pub fn visualize(input: &str, _params: &Params) -> Result<String> {
+    visualize::render(input).context("Failed to visualize")
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct IVec2 {
     pub x: i32,
@@ -29,6 +45,13 @@ fn split(col: i32) -> (i32, i32) {
     (col - 1, col + 1)
 }
 
+fn beam_split_event(mirror: IVec2, beams: usize) -> Event {
+    Event::new("beam_split")
+        .with("x", mirror.x)
+        .with("y", mirror.y)
+        .with("beams", beams)
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct TachyonManifold {
     start: IVec2,
@@ -46,6 +69,7 @@ impl TachyonManifold {
                 let (l, r) = split(col);
                 cols.insert(l);
                 cols.insert(r);
+                explain::record(|| beam_split_event(*mirror, cols.len()));
             }
         }
 
@@ -145,7 +169,7 @@ mod tests {
 
     #[test]
     fn test_parse_input() {
-        let actual = TachyonManifold::from_str(&raw_input()).unwrap();
+        let actual = TachyonManifold::from_str(raw_input()).unwrap();
         let expected = setup_test_input();
 
         assert_eq!(expected, actual);
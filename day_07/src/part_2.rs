@@ -15,8 +15,8 @@ pub enum PartError {
 pub fn run(input: &str) -> Result<u64, PartError> {
     let manifold = TachyonManifold::from_str(input)?;
     let map = Map::from(manifold);
-    let count = map.count_all_paths_to_all_leaves();
-    count
+
+    map.count_all_paths_to_all_leaves()
 }
 #[derive(Debug, PartialEq, Eq)]
 struct Map {
@@ -69,9 +69,10 @@ impl From<TachyonManifold> for Map {
                 let (l, r) = split(col);
                 cols.entry(l).or_default().push(*mirror);
                 cols.entry(r).or_default().push(*mirror);
+                explain::record(|| beam_split_event(*mirror, cols.len()));
             }
         }
-        let leaves = cols.values().cloned().map(|v| Node::new(v)).collect();
+        let leaves = cols.values().cloned().map(Node::new).collect();
         Map { nodes, leaves }
     }
 }
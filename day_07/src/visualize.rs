@@ -0,0 +1,63 @@
+use std::collections::{BTreeSet, HashSet};
+
+use common::svg::Svg;
+
+use crate::*;
+
+const BEAM: &str = "#f5c242";
+const START: &str = "#3a9a3a";
+const SPLIT: &str = "#d33d3d";
+const UNUSED: &str = "#999999";
+
+/// Draws the manifold with the beams going down it, and the splitters that part 1 counts picked out.
+pub fn render(input: &str) -> Result<String, ParseError> {
+    let manifold = TachyonManifold::from_str(input)?;
+    let width = input.trim().lines().map(str::len).max().unwrap_or(0);
+    let mut svg = Svg::new(0.0, 0.0, width as f64, manifold.rows as f64);
+    // Follows the beams a row at a time, the same way `count_reflections` does.
+    let mut cols = BTreeSet::new();
+    let mut used = HashSet::new();
+    let mut mirrors = manifold.mirrors.iter().peekable();
+    for y in 0..manifold.rows as i32 {
+        if y == manifold.start.y {
+            cols.insert(manifold.start.x);
+        }
+        while let Some(mirror) = mirrors.next_if(|mirror| mirror.y == y) {
+            if cols.remove(&mirror.x) {
+                used.insert(*mirror);
+                let (l, r) = split(mirror.x);
+                cols.insert(l);
+                cols.insert(r);
+            }
+        }
+        for &x in &cols {
+            svg = svg.rect(x as f64 + 0.35, y as f64, 0.3, 1.0, BEAM);
+        }
+    }
+    for mirror in &manifold.mirrors {
+        let fill = if used.contains(mirror) { SPLIT } else { UNUSED };
+        let (x, y) = (mirror.x as f64, mirror.y as f64);
+        svg = svg.polygon(
+            &[(x + 0.5, y + 0.1), (x + 0.9, y + 0.9), (x + 0.1, y + 0.9)],
+            fill,
+            fill,
+        );
+    }
+    let start = manifold.start;
+    svg = svg.circle(start.x as f64 + 0.5, start.y as f64 + 0.5, 0.4, START);
+    Ok(svg.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let svg = render(include_str!("../inputs/test.txt")).unwrap();
+        assert!(svg.contains(r#"viewBox="0 0 15 16""#));
+        // Every splitter part 1 counts is drawn in red.
+        assert_eq!(svg.matches(&format!(r#"fill="{SPLIT}""#)).count(), 21);
+        assert_eq!(svg.matches("<polygon").count(), 22);
+    }
+}
@@ -1,17 +1,38 @@
 use std::str::FromStr;
 
 use anyhow::{Context, Result};
+pub use common::input::Normalization;
+pub use common::params::Params;
+use common::params::{Param, ParamSpec};
 use thiserror::Error;
 
 mod part_1;
 mod part_2;
+mod visualize;
 
-pub fn run_part_1(input: &str, n_pairs: usize) -> Result<u64> {
-    part_1::run(input, n_pairs).context("Failed to run part 1")
+/// How the input is cleaned up before it is parsed.
+pub const NORMALIZATION: Normalization = Normalization::Full;
+
+/// The number of closest pairs of junction boxes to connect in part 1.
+/// The puzzle uses 1000, but the example only connects 10.
+pub const N_PAIRS: Param<usize> = Param::new(
+    "n_pairs",
+    1000,
+    "Number of closest junction box pairs to connect in part 1",
+);
+pub const PARAMS: &[&dyn ParamSpec] = &[&N_PAIRS];
+
+pub fn run_part_1(input: &str, params: &Params) -> Result<u64> {
+    part_1::run(input, params.get(&N_PAIRS)).context("Failed to run part 1")
+}
+
+pub fn run_part_2(input: &str, _params: &Params) -> Result<u64> {
+    part_2::run(input).context("Failed to run part 2")
 }
 
-pub fn run_part_2(input: &str, n_pairs: usize) -> Result<u64> {
-    part_2::run(input, n_pairs).context("Failed to run part 2")
+/// Draws the junction boxes and part 1's cables as an SVG picture for `--visualize`.
+pub fn visualize(input: &str, params: &Params) -> Result<String> {
+    visualize::render(input, params.get(&N_PAIRS)).context("Failed to visualize")
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -31,7 +52,7 @@ impl FromStr for Decorations {
         let junction_boxes = s
             .trim()
             .lines()
-            .map(|ln| JunctionBox::from_str(ln))
+            .map(JunctionBox::from_str)
             .collect::<Result<Vec<_>, _>>()?;
         Ok(Decorations { junction_boxes })
     }
@@ -107,6 +128,13 @@ mod tests {
     fn jb(x: i32, y: i32, z: i32) -> JunctionBox {
         JunctionBox { x, y, z }
     }
+    #[test]
+    fn test_parse_decorations() {
+        let input = include_str!("../inputs/test.txt");
+        let actual = Decorations::from_str(input).unwrap();
+        assert_eq!(actual, setup_test_input());
+    }
+
     #[test]
     fn test_junction_box_distance() {
         let box1 = jb(1, 2, 3);
@@ -18,8 +18,18 @@ struct Args {
     input: std::path::PathBuf,
     #[arg(short, long, default_value = "both")]
     part: Part,
-    #[arg(short, long, default_value = "1000")]
-    n_pairs: usize,
+    /// Override a puzzle parameter, e.g. `--param n_pairs=10`. Can be repeated.
+    #[arg(long = "param", value_name = "KEY=VALUE", value_parser = common::params::parse_assignment)]
+    params: Vec<(String, String)>,
+    /// Print every step the solution takes. Pass a file to write the steps to it as JSON lines instead.
+    #[arg(long, value_name = "FILE", num_args = 0..=1, require_equals = true, default_missing_value = "-")]
+    explain: Option<std::path::PathBuf>,
+    /// After answering, time each part over this many in-process runs and print the fastest, median and slowest.
+    #[arg(long, value_name = "RUNS", num_args = 0..=1, require_equals = true, default_missing_value = "10")]
+    bench: Option<usize>,
+    /// Draw the puzzle as an SVG picture and write it to a file.
+    #[arg(long, value_name = "FILE")]
+    visualize: Option<std::path::PathBuf>,
 }
 
 fn main() -> Result<()> {
@@ -29,31 +39,84 @@ fn main() -> Result<()> {
     env_logger::Builder::new()
         .filter_level(args.verbosity.into())
         .init();
-    // Read the input file into a string.
-    let input = read_input_file_to_string(&args.input)
+    // Start recording the steps of the solution if asked to.
+    if let Some(path) = &args.explain {
+        start_explaining(path)
+            .with_context(|| format!("Failed to create explain file {}", path.display()))?;
+    }
+    // Read the input file into a string, cleaning up line endings and the like on the way.
+    let input = common::input::read_normalized(&args.input, solution::NORMALIZATION)
         .with_context(|| format!("Failed to read input file {}", args.input.display()))?;
+    // Collect the puzzle parameters for this run.
+    let params = read_params(&args.input, &args.params).context("Invalid parameters")?;
+    // Draw the puzzle if asked to, before the answers in case a part fails.
+    if let Some(path) = &args.visualize {
+        let svg = solution::visualize(&input, &params)?;
+        std::fs::write(path, svg)
+            .with_context(|| format!("Failed to write picture to {}", path.display()))?;
+    }
 
     // Run the appropriate part(s) of the solution.
     match args.part {
         Part::One => {
-            let part_1 = solution::run_part_1(&input, args.n_pairs)?;
+            common::explain::start_part(1);
+            let part_1 = solution::run_part_1(&input, &params)?;
             println!("Part 1: {}", part_1);
         }
         Part::Two => {
-            let part_2 = solution::run_part_2(&input, args.n_pairs)?;
+            common::explain::start_part(2);
+            let part_2 = solution::run_part_2(&input, &params)?;
             println!("Part 2: {}", part_2);
         }
         Part::Both => {
-            let part_1 = solution::run_part_1(&input, args.n_pairs)?;
-            let part_2 = solution::run_part_2(&input, args.n_pairs)?;
+            common::explain::start_part(1);
+            let part_1 = solution::run_part_1(&input, &params)?;
+            common::explain::start_part(2);
+            let part_2 = solution::run_part_2(&input, &params)?;
             println!("Part 1: {}", part_1);
             println!("Part 2: {}", part_2);
         }
     }
+    common::explain::finish().context("Failed to write explain output")?;
+    // Time the parts again if asked to, now that nothing is being recorded.
+    if let Some(runs) = args.bench {
+        if matches!(args.part, Part::One | Part::Both) {
+            println!(
+                "Part 1 time: {}",
+                common::bench::time(runs, || solution::run_part_1(&input, &params))
+            );
+        }
+        if matches!(args.part, Part::Two | Part::Both) {
+            println!(
+                "Part 2 time: {}",
+                common::bench::time(runs, || solution::run_part_2(&input, &params))
+            );
+        }
+    }
 
     Ok(())
 }
 
-fn read_input_file_to_string(path: &std::path::Path) -> Result<String, std::io::Error> {
-    std::fs::read_to_string(path)
+/// Builds the parameters for the run. Values come from the params file that sits next to the input
+/// (e.g. `inputs/test.params`) and can then be overridden on the command line.
+fn read_params(
+    input: &std::path::Path,
+    overrides: &[(String, String)],
+) -> Result<solution::Params, common::params::ParamError> {
+    let mut assignments = common::params::read_params_file_for(input)?;
+    assignments.extend(overrides.iter().cloned());
+    solution::Params::new(solution::PARAMS, assignments)
+}
+
+/// Records the steps of the solution, pretty printed to stderr if `path` is `-` or as JSON lines to the file otherwise.
+fn start_explaining(path: &std::path::Path) -> std::io::Result<()> {
+    use common::explain::Sink;
+    let sink = if path == std::path::Path::new("-") {
+        Sink::Pretty(Box::new(std::io::stderr()))
+    } else {
+        let file = std::fs::File::create(path)?;
+        Sink::JsonLines(Box::new(std::io::BufWriter::new(file)))
+    };
+    common::explain::enable(sink);
+    Ok(())
 }
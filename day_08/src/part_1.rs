@@ -41,7 +41,7 @@ impl Network {
     fn measure_circuit_lengths(&self) -> Vec<usize> {
         let mut lengths = Vec::new();
         let mut visited = HashSet::new();
-        for (junction, _) in &self.nodes {
+        for junction in self.nodes.keys() {
             if visited.insert(*junction) {
                 let connected_junctions = self.get_connected_junctions(junction);
                 let circuit_length = connected_junctions.len();
@@ -84,7 +84,7 @@ struct Node {
 /// Calculates all pairwise distances between junction boxes.
 /// Returns a vector of tuples containing each pair of junction boxes ordered by distance.
 /// Lowest distance first.
-fn order_all_junction_box_pairs_by_distance(
+pub(crate) fn order_all_junction_box_pairs_by_distance(
     decorations: &Decorations,
 ) -> Vec<(JunctionBox, JunctionBox)> {
     let mut distances = Vec::new();
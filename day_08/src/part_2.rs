@@ -10,11 +10,11 @@ pub enum PartError {
     ParsingError(#[from] ParseError),
 }
 
-pub fn run(input: &str, _: usize) -> Result<u64, PartError> {
+pub fn run(input: &str) -> Result<u64, PartError> {
     let decorations = Decorations::from_str(input)?;
     let last_pair = last_pair_needed_to_connect_all_junction_boxes(&decorations);
     let product = last_pair.unwrap().0.x as u64 * last_pair.unwrap().1.x as u64;
-    Ok(product as u64)
+    Ok(product)
 }
 /// Ignoring the "depth limit" that we had on part 1, my solution is just a brute force version of part 1.
 /// It takes the Decorations and performs the same counting circuits method for the network, but just does it after every connection is added.
@@ -48,7 +48,7 @@ impl Network {
     fn measure_circuit_lengths(&self) -> Vec<usize> {
         let mut lengths = Vec::new();
         let mut visited = HashSet::new();
-        for (junction, _) in &self.nodes {
+        for junction in self.nodes.keys() {
             if visited.insert(*junction) {
                 let connected_junctions = self.get_connected_junctions(junction);
                 let circuit_length = connected_junctions.len();
@@ -0,0 +1,60 @@
+use common::svg::Svg;
+
+use crate::*;
+
+const BOX: &str = "#2b6cb0";
+const CABLE: &str = "#e07b39";
+
+/// Draws the junction boxes seen from above, with the cables part 1 connects between the closest pairs.
+pub fn render(input: &str, n_pairs: usize) -> Result<String, ParseError> {
+    let decorations = Decorations::from_str(input)?;
+    let boxes = &decorations.junction_boxes;
+    let (min_x, max_x) = bounds(boxes.iter().map(|b| b.x));
+    let (min_y, max_y) = bounds(boxes.iter().map(|b| b.y));
+    let span = (max_x - min_x).max(max_y - min_y).max(1.0);
+    let radius = span / 100.0;
+    // A margin around the edge so the boxes there aren't cut in half.
+    let mut svg = Svg::new(
+        min_x - 2.0 * radius,
+        min_y - 2.0 * radius,
+        max_x - min_x + 4.0 * radius,
+        max_y - min_y + 4.0 * radius,
+    );
+    let pairs = part_1::order_all_junction_box_pairs_by_distance(&decorations);
+    for (a, b) in pairs.into_iter().take(n_pairs) {
+        svg = svg.line(
+            (a.x as f64, a.y as f64),
+            (b.x as f64, b.y as f64),
+            CABLE,
+            2.0,
+        );
+    }
+    for b in boxes {
+        svg = svg.circle(b.x as f64, b.y as f64, radius, BOX);
+    }
+    Ok(svg.to_string())
+}
+
+/// The smallest and largest of `values`, or zeros if there are none.
+fn bounds(values: impl Iterator<Item = i32>) -> (f64, f64) {
+    values
+        .fold(None, |bounds: Option<(i32, i32)>, v| match bounds {
+            Some((min, max)) => Some((min.min(v), max.max(v))),
+            None => Some((v, v)),
+        })
+        .map_or((0.0, 0.0), |(min, max)| (min as f64, max as f64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let svg = render(include_str!("../inputs/test.txt"), 10).unwrap();
+        assert_eq!(svg.matches("<circle").count(), 20);
+        assert_eq!(svg.matches("<line").count(), 10);
+        // The two closest boxes are the first pair to be connected.
+        assert!(svg.contains(r#"<line x1="162" y1="817" x2="425" y2="690""#));
+    }
+}
@@ -15,18 +15,22 @@ pub fn run(input: &str) -> Result<u64, PartError> {
 }
 
 fn find_largest_tile_area(tiles: &Tiles) -> u64 {
+    find_largest_tile_rectangle(tiles).map_or(0, |(a, b)| calculate_area(a, b))
+}
+
+/// The pair of red tiles at opposite corners of the largest rectangle.
+pub(crate) fn find_largest_tile_rectangle(tiles: &Tiles) -> Option<(IVec2, IVec2)> {
     tiles
         .tiles
         .iter()
         .combinations(2)
-        .map(|pair| calculate_area(*pair[0], *pair[1]))
-        .max()
-        .unwrap_or(0)
+        .max_by_key(|pair| calculate_area(*pair[0], *pair[1]))
+        .map(|pair| (*pair[0], *pair[1]))
 }
 
 fn calculate_area(a: IVec2, b: IVec2) -> u64 {
-    let width = (b.x - a.x).abs() as u64 + 1; // Plus one to include both endpoints
-    let height = (b.y - a.y).abs() as u64 + 1; // Plus one to include both endpoints
+    let width = (b.x - a.x).unsigned_abs() as u64 + 1; // Plus one to include both endpoints
+    let height = (b.y - a.y).unsigned_abs() as u64 + 1; // Plus one to include both endpoints
     width * height
 }
 
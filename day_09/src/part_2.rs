@@ -8,14 +8,12 @@ pub enum PartError {
     ParsingError(#[from] ParseError),
 }
 
-pub fn run(input: &str) -> Result<u64, PartError> {
+pub fn run(_input: &str) -> Result<u64, PartError> {
     todo!("Implement solution");
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     #[test]
     #[ignore]
     fn test_run_part() {
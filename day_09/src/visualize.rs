@@ -0,0 +1,56 @@
+use common::svg::Svg;
+
+use crate::*;
+
+const RED: &str = "#d33d3d";
+const GREEN: &str = "#8fd18f";
+const RECTANGLE: &str = "#2b6cb0";
+
+/// Draws the loop of red and green tiles, with the largest rectangle from part 1 outlined.
+/// Each tile is a unit square, so the outlines go through the middle of the tiles.
+pub fn render(input: &str) -> Result<String, ParseError> {
+    let tiles = Tiles::from_str(input)?;
+    let (min_x, max_x) = bounds(tiles.tiles.iter().map(|t| t.x));
+    let (min_y, max_y) = bounds(tiles.tiles.iter().map(|t| t.y));
+    let mut svg = Svg::new(min_x, min_y, max_x - min_x + 1.0, max_y - min_y + 1.0);
+    let centres = tiles
+        .tiles
+        .iter()
+        .map(|t| (t.x as f64 + 0.5, t.y as f64 + 0.5))
+        .collect::<Vec<_>>();
+    svg = svg.polygon(&centres, GREEN, RED);
+    if let Some((a, b)) = part_1::find_largest_tile_rectangle(&tiles) {
+        let (left, right) = (a.x.min(b.x) as f64, a.x.max(b.x) as f64 + 1.0);
+        let (top, bottom) = (a.y.min(b.y) as f64, a.y.max(b.y) as f64 + 1.0);
+        svg = svg.polygon(
+            &[(left, top), (right, top), (right, bottom), (left, bottom)],
+            "none",
+            RECTANGLE,
+        );
+    }
+    Ok(svg.to_string())
+}
+
+/// The smallest and largest of `values`, or zeros if there are none.
+fn bounds(values: impl Iterator<Item = i32>) -> (f64, f64) {
+    values
+        .fold(None, |bounds: Option<(i32, i32)>, v| match bounds {
+            Some((min, max)) => Some((min.min(v), max.max(v))),
+            None => Some((v, v)),
+        })
+        .map_or((0.0, 0.0), |(min, max)| (min as f64, max as f64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let svg = render(include_str!("../inputs/test.txt")).unwrap();
+        assert!(svg.contains(r#"viewBox="2 1 10 7""#));
+        assert!(svg.contains(r#"points="7.5,1.5 11.5,1.5 11.5,7.5"#));
+        // The first of the 10 by 5 rectangles, from (2, 3) to (11, 7).
+        assert!(svg.contains(r#"points="2,3 12,3 12,8 2,8""#));
+    }
+}
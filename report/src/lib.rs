@@ -0,0 +1,452 @@
+//! Runs every day on one of its inputs and renders the results as a single HTML page.
+//!
+//! Each day is built and run as its own binary, since the days are independent crates. The timings come from the
+//! days' `--bench` mode, which times each part inside the process so start-up isn't counted, and days that can draw
+//! their puzzle with `--visualize` have the picture embedded. The page is self-contained apart from the links to each
+//! day's source files, which are relative to the root of the repository.
+
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use common::{bench::Timing, params::ParamError};
+
+/// How one part of a day compares to its expected answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    /// There's an answer but nothing to check it against.
+    Unchecked,
+    /// The day didn't give an answer for this part.
+    Missing,
+}
+impl Status {
+    fn class(self) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Fail => "fail",
+            Status::Unchecked => "unchecked",
+            Status::Missing => "missing",
+        }
+    }
+}
+
+/// The outcome of running a single day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayRun {
+    /// The name of the day's folder, e.g. `day_03`.
+    pub day: String,
+    /// The answers to part 1 and part 2, as printed by the day.
+    pub answers: [Option<String>; 2],
+    /// The answers from the input's `.expected` file, if it has one.
+    pub expected: [Option<String>; 2],
+    /// How long each part took in the day's benchmark mode.
+    pub timings: [Option<Timing>; 2],
+    /// The day's picture of its puzzle as SVG, if it can draw one.
+    pub visualization: Option<String>,
+    /// The day's source files, relative to the root of the repository.
+    pub sources: Vec<PathBuf>,
+    /// Why the day couldn't be built or run, if it couldn't.
+    pub error: Option<String>,
+}
+impl DayRun {
+    fn new(day: String) -> Self {
+        DayRun {
+            day,
+            answers: [None, None],
+            expected: [None, None],
+            timings: [None, None],
+            visualization: None,
+            sources: vec![],
+            error: None,
+        }
+    }
+    /// The status of `part`, which is 1 or 2.
+    pub fn status(&self, part: usize) -> Status {
+        match (&self.answers[part - 1], &self.expected[part - 1]) {
+            (None, _) => Status::Missing,
+            (Some(_), None) => Status::Unchecked,
+            (Some(answer), Some(expected)) if answer == expected => Status::Pass,
+            (Some(_), Some(_)) => Status::Fail,
+        }
+    }
+}
+
+/// The `day_*` folders under `root` that hold a crate, in order.
+pub fn find_days(root: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut days = std::fs::read_dir(root)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("day_"))
+                && path.join("Cargo.toml").is_file()
+        })
+        .collect::<Vec<_>>();
+    days.sort();
+    Ok(days)
+}
+
+/// The package name from a `Cargo.toml`, which is also the name of the binary it builds.
+pub fn package_name(manifest: &str) -> Option<String> {
+    manifest
+        .lines()
+        .skip_while(|ln| ln.trim() != "[package]")
+        .find_map(|ln| {
+            let (key, value) = ln.split_once('=')?;
+            (key.trim() == "name").then(|| value.trim().trim_matches('"').to_string())
+        })
+}
+
+/// Picks the answers out of a day's output, which prints them as `Part 1: <answer>` and `Part 2: <answer>`.
+pub fn parse_answers(stdout: &str) -> [Option<String>; 2] {
+    let answer = |part: &str| {
+        stdout
+            .lines()
+            .find_map(|ln| ln.strip_prefix(part))
+            .map(|answer| answer.trim().to_string())
+    };
+    [answer("Part 1:"), answer("Part 2:")]
+}
+
+/// Picks the timings out of a day's `--bench` output, which prints them as `Part 1 time: <timing>`.
+pub fn parse_timings(stdout: &str) -> [Option<Timing>; 2] {
+    let timing = |part: &str| {
+        stdout
+            .lines()
+            .find_map(|ln| ln.strip_prefix(part))
+            .and_then(|timing| timing.trim().parse().ok())
+    };
+    [timing("Part 1 time:"), timing("Part 2 time:")]
+}
+
+/// Reads the expected answers for an input from the `.expected` file next to it, e.g. `inputs/test.expected`.
+/// It is written like a params file, with `part_1=<answer>` and `part_2=<answer>` lines.
+pub fn read_expected(input: &Path) -> Result<[Option<String>; 2], ParamError> {
+    let path = input.with_extension("expected");
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok([None, None]),
+        Err(e) => return Err(ParamError::Io(path.display().to_string(), e)),
+    };
+    let mut expected = [None, None];
+    for (key, value) in common::params::parse_params_file(&contents)? {
+        match key.as_str() {
+            "part_1" => expected[0] = Some(value),
+            "part_2" => expected[1] = Some(value),
+            _ => {}
+        }
+    }
+    Ok(expected)
+}
+
+/// Builds the day in `dir` in release mode and runs it on `inputs/<input>`, timing each part over `runs` runs.
+/// Anything that goes wrong is kept in the result rather than stopping the whole report.
+pub fn run_day(root: &Path, dir: &Path, input: &str, runs: usize) -> DayRun {
+    let name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut run = DayRun::new(name);
+    run.sources = source_files(dir)
+        .into_iter()
+        .map(|path| {
+            path.strip_prefix(root)
+                .map(Path::to_path_buf)
+                .unwrap_or(path)
+        })
+        .collect();
+    if let Err(error) = try_run_day(dir, input, runs, &mut run) {
+        run.error = Some(error);
+    }
+    run
+}
+
+fn try_run_day(dir: &Path, input: &str, runs: usize, run: &mut DayRun) -> Result<(), String> {
+    let input_path = dir.join("inputs").join(input);
+    if !input_path.is_file() {
+        return Err(format!("There is no inputs/{input}"));
+    }
+    run.expected = read_expected(&input_path).map_err(|e| e.to_string())?;
+    let manifest = std::fs::read_to_string(dir.join("Cargo.toml")).map_err(|e| e.to_string())?;
+    let binary = package_name(&manifest).ok_or("Cargo.toml has no package name")?;
+    let build = Command::new("cargo")
+        .args(["build", "--release", "--quiet"])
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("Failed to run cargo: {e}"))?;
+    if !build.status.success() {
+        return Err(format!(
+            "Failed to build\n{}",
+            String::from_utf8_lossy(&build.stderr)
+        ));
+    }
+    let binary = dir.join("target").join("release").join(binary);
+    let run_with = |args: &[&str]| {
+        Command::new(&binary)
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .map_err(|e| format!("Failed to run {}: {e}", binary.display()))
+    };
+    let help = run_with(&["--help"])?;
+    let picture = dir.join("target").join("report.svg");
+    let _ = std::fs::remove_file(&picture);
+    let mut args = vec![format!("--bench={runs}")];
+    if String::from_utf8_lossy(&help.stdout).contains("--visualize") {
+        args.push(format!("--visualize={}", picture.display()));
+    }
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let input_arg = format!("inputs/{input}");
+    // Some days take the input as a positional argument and some as `--input`. Clap exits with 2 on bad usage.
+    let mut output = run_with(&[&[input_arg.as_str()], &args[..]].concat())?;
+    if output.status.code() == Some(2) {
+        output = run_with(&[&["--input", &input_arg], &args[..]].concat())?;
+    }
+    // The picture is drawn before the answers, so it's there even if a part fails.
+    run.visualization = std::fs::read_to_string(&picture).ok();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    run.answers = parse_answers(&stdout);
+    run.timings = parse_timings(&stdout);
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+    }
+    Ok(())
+}
+
+/// The `.rs` files in the day's `src` folder, sorted.
+fn source_files(dir: &Path) -> Vec<PathBuf> {
+    let mut sources = std::fs::read_dir(dir.join("src"))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    sources.sort();
+    sources
+}
+
+/// Renders the runs as a self-contained HTML page.
+pub fn render(title: &str, runs: &[DayRun]) -> String {
+    let checked = runs
+        .iter()
+        .flat_map(|run| [run.status(1), run.status(2)])
+        .filter(|status| matches!(status, Status::Pass | Status::Fail))
+        .collect::<Vec<_>>();
+    let passed = checked.iter().filter(|&&s| s == Status::Pass).count();
+    // The time bars are scaled so the slowest part fills the whole width.
+    let slowest = runs
+        .iter()
+        .flat_map(|run| run.timings.iter().flatten())
+        .map(|timing| timing.median)
+        .max()
+        .unwrap_or_default();
+
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; }}
+th, td {{ border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; vertical-align: top; }}
+.pass {{ background: #d4f7d4; }}
+.fail {{ background: #f7d4d4; }}
+.missing {{ background: #eee; color: #888; }}
+.bar {{ background: #6a9fd4; height: 0.8em; }}
+.time {{ width: 16em; }}
+.time div + span {{ font-size: 0.8em; }}
+figure {{ display: inline-block; margin: 1em; }}
+pre {{ margin: 0; white-space: pre-wrap; color: #a00; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p>{passed} of {checked} checked answers match.</p>
+<table>
+<tr><th>Day</th><th>Part 1</th><th>Part 2</th><th>Time (median of runs, fastest to slowest)</th><th>Source</th></tr>
+"#,
+        title = escape(title),
+        checked = checked.len(),
+    );
+    for run in runs {
+        let _ = write!(html, "<tr><td>{}</td>", escape(&run.day));
+        for part in [1, 2] {
+            let status = run.status(part);
+            let answer = run.answers[part - 1].as_deref().unwrap_or("none");
+            let expected = match (status, &run.expected[part - 1]) {
+                (Status::Fail, Some(expected)) => format!(" (expected {})", escape(expected)),
+                _ => String::new(),
+            };
+            let _ = write!(
+                html,
+                r#"<td class="{}">{}{expected}</td>"#,
+                status.class(),
+                escape(answer)
+            );
+        }
+        if run.timings.iter().all(Option::is_none) {
+            html.push_str(r#"<td class="time missing">not run</td>"#);
+        } else {
+            html.push_str(r#"<td class="time">"#);
+            for (part, timing) in run.timings.iter().enumerate() {
+                let Some(timing) = timing else { continue };
+                let width = if slowest.is_zero() {
+                    100.0
+                } else {
+                    timing.median.as_secs_f64() / slowest.as_secs_f64() * 100.0
+                };
+                let _ = write!(
+                    html,
+                    r#"<div class="bar" style="width: {width:.1}%"></div><span>Part {}: {} ({} to {})</span>"#,
+                    part + 1,
+                    format_duration(timing.median),
+                    format_duration(timing.min),
+                    format_duration(timing.max)
+                );
+            }
+            html.push_str("</td>");
+        }
+        let links = run
+            .sources
+            .iter()
+            .map(|path| {
+                let path = escape(&path.to_string_lossy().replace('\\', "/"));
+                let name = path.rsplit('/').next().unwrap_or(&path).to_string();
+                format!(r#"<a href="{path}">{name}</a>"#)
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let _ = writeln!(html, "<td>{links}</td></tr>");
+        if let Some(error) = &run.error {
+            let _ = writeln!(
+                html,
+                "<tr><td></td><td colspan=\"4\"><pre>{}</pre></td></tr>",
+                escape(error.trim())
+            );
+        }
+    }
+    html.push_str("</table>\n");
+    if runs.iter().any(|run| run.visualization.is_some()) {
+        html.push_str("<h2>Visualizations</h2>\n");
+        for run in runs {
+            if let Some(svg) = &run.visualization {
+                let _ = writeln!(
+                    html,
+                    "<figure>\n<figcaption>{}</figcaption>\n{}\n</figure>",
+                    escape(&run.day),
+                    svg.trim()
+                );
+            }
+        }
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// A duration in whichever of ms and µs reads best.
+fn format_duration(duration: std::time::Duration) -> String {
+    if duration.as_millis() > 0 {
+        format!("{:.1} ms", duration.as_secs_f64() * 1000.0)
+    } else {
+        format!("{:.1} µs", duration.as_secs_f64() * 1_000_000.0)
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn timing(millis: u64) -> Timing {
+        Timing {
+            runs: 10,
+            min: Duration::from_millis(millis),
+            median: Duration::from_millis(millis),
+            max: Duration::from_millis(millis),
+        }
+    }
+
+    fn day_run(answers: [Option<&str>; 2], expected: [Option<&str>; 2]) -> DayRun {
+        DayRun {
+            answers: answers.map(|a| a.map(str::to_string)),
+            expected: expected.map(|e| e.map(str::to_string)),
+            timings: [Some(timing(5)), None],
+            sources: vec![PathBuf::from("day_03/src/lib.rs")],
+            ..DayRun::new("day_03".to_string())
+        }
+    }
+
+    #[test]
+    fn test_parse_answers() {
+        let stdout = "Part 1 batteries:\n[9]8\nPart 1: 357\nPart 2: 3121910778619\n";
+        assert_eq!(
+            parse_answers(stdout),
+            [Some("357".to_string()), Some("3121910778619".to_string())]
+        );
+        assert_eq!(parse_answers("Part 2: 7"), [None, Some("7".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_timings() {
+        let stdout = "Part 1: 357\nPart 2: 7\nPart 1 time: runs=10 min=5000000ns median=5000000ns max=5000000ns\n";
+        assert_eq!(parse_timings(stdout), [Some(timing(5)), None]);
+    }
+
+    #[test]
+    fn test_package_name() {
+        let manifest =
+            "[package]\nname = \"template\"\nversion = \"0.1.0\"\n\n[lib]\nname = \"solution\"\n";
+        assert_eq!(package_name(manifest), Some("template".to_string()));
+        assert_eq!(package_name("[lib]\nname = \"solution\"\n"), None);
+    }
+
+    #[test]
+    fn test_status() {
+        let run = day_run([Some("1"), Some("2")], [Some("1"), Some("3")]);
+        assert_eq!((run.status(1), run.status(2)), (Status::Pass, Status::Fail));
+        let run = day_run([Some("1"), None], [None, Some("2")]);
+        assert_eq!(
+            (run.status(1), run.status(2)),
+            (Status::Unchecked, Status::Missing)
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let mut run = day_run([Some("1"), Some("<2>")], [Some("1"), Some("3")]);
+        run.error = Some("thread 'main' panicked".to_string());
+        let html = render("Report", &[run]);
+        assert!(html.contains("<p>1 of 2 checked answers match.</p>"));
+        assert!(html.contains(r#"<td class="fail">&lt;2&gt; (expected 3)</td>"#));
+        assert!(html.contains(r#"<a href="day_03/src/lib.rs">lib.rs</a>"#));
+        assert!(html.contains("<pre>thread 'main' panicked</pre>"));
+        assert!(html.contains("<span>Part 1: 5.0 ms (5.0 ms to 5.0 ms)</span>"));
+        assert!(!html.contains("Visualizations"));
+    }
+
+    #[test]
+    fn test_render_visualization() {
+        let mut run = day_run([Some("1"), Some("2")], [None, None]);
+        run.visualization = Some("<svg></svg>\n".to_string());
+        let html = render("Report", &[run]);
+        assert!(html.contains("<figure>\n<figcaption>day_03</figcaption>\n<svg></svg>\n</figure>"));
+    }
+}
@@ -0,0 +1,54 @@
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Run every day and write the answers, checks, timings and source links to a single HTML page.
+    Report {
+        /// The root of the repository, holding the `day_*` folders.
+        #[arg(long, default_value = ".")]
+        root: std::path::PathBuf,
+        /// Which file in each day's `inputs` folder to run on.
+        #[arg(short, long, default_value = "test.txt")]
+        input: String,
+        /// Where to write the page. The source links only work if it sits in the root.
+        #[arg(short, long, default_value = "report.html")]
+        out: std::path::PathBuf,
+        /// How many times each part is run in the days' benchmark mode to time it.
+        #[arg(long, default_value_t = 10)]
+        runs: usize,
+    },
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    match args.command {
+        Command::Report {
+            root,
+            input,
+            out,
+            runs,
+        } => {
+            let days = report::find_days(&root)
+                .with_context(|| format!("Failed to look for days in {}", root.display()))?;
+            let runs = days
+                .iter()
+                .map(|day| {
+                    eprintln!("Running {}", day.display());
+                    report::run_day(&root, day, &input, runs)
+                })
+                .collect::<Vec<_>>();
+            let html = report::render(&format!("Advent of Code 2025: {input}"), &runs);
+            std::fs::write(&out, html)
+                .with_context(|| format!("Failed to write {}", out.display()))?;
+            println!("Wrote {}", out.display());
+        }
+    }
+    Ok(())
+}